@@ -16,6 +16,8 @@ pub struct ClassDef {
     pub name: String,
     /// The name of the superclass.
     pub super_class: Option<String>,
+    /// The comment documenting the class, if any.
+    pub comment: Option<String>,
     /// The locals for instances of that class.
     pub instance_locals: Vec<String>,
     /// The methods declared for instances of that class.
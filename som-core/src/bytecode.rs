@@ -11,38 +11,73 @@ pub enum Bytecode {
     PushBlock(u8),
     PushConstant(u8),
     PushGlobal(u8),
+    PushGlobalCached(u8),
     Pop,
+    Pop2,
     PopLocal(u8, u8),
     PopArgument(u8, u8),
     PopField(u8),
+    NilLocal(u8),
     Send(u8),
     SuperSend(u8),
     ReturnLocal,
     ReturnNonLocal,
 }
 
+/// The number of distinct instructions.
+pub const NB_BYTECODES: usize = 19;
+
 impl Bytecode {
+    /// Get the instruction's opcode, which indexes into `NAMES` and `PADDED_NAMES`.
+    #[rustfmt::skip]
+    pub fn opcode(self) -> u8 {
+        match self {
+            Self::Halt                  => 0,
+            Self::Dup                   => 1,
+            Self::PushLocal(_, _)       => 2,
+            Self::PushArgument(_, _)    => 3,
+            Self::PushField(_)          => 4,
+            Self::PushBlock(_)          => 5,
+            Self::PushConstant(_)       => 6,
+            Self::PushGlobal(_)         => 7,
+            Self::PushGlobalCached(_)   => 8,
+            Self::Pop                   => 9,
+            Self::Pop2                  => 10,
+            Self::PopLocal(_, _)        => 11,
+            Self::PopArgument(_, _)     => 12,
+            Self::PopField(_)           => 13,
+            Self::NilLocal(_)           => 14,
+            Self::Send(_)               => 15,
+            Self::SuperSend(_)          => 16,
+            Self::ReturnLocal           => 17,
+            Self::ReturnNonLocal        => 18,
+        }
+    }
+
     /// Get the instruction's name.
     #[rustfmt::skip]
     pub fn name(self) -> &'static str {
         // NAMES[self as usize]
         match self {
-            Self::Halt               => "HALT",
-            Self::Dup                => "DUP",
-            Self::PushLocal(_, _)    => "PUSH_LOCAL",
-            Self::PushArgument(_, _) => "PUSH_ARGUMENT",
-            Self::PushField(_)       => "PUSH_FIELD",
-            Self::PushBlock(_)       => "PUSH_BLOCK",
-            Self::PushConstant(_)    => "PUSH_CONSTANT",
-            Self::PushGlobal(_)      => "PUSH_GLOBAL",
-            Self::Pop                => "POP",
-            Self::PopLocal(_, _)     => "POP_LOCAL",
-            Self::PopArgument(_, _)  => "POP_ARGUMENT",
-            Self::PopField(_)        => "POP_FIELD",
-            Self::Send(_)            => "SEND",
-            Self::SuperSend(_)       => "SUPER_SEND",
-            Self::ReturnLocal        => "RETURN_LOCAL",
-            Self::ReturnNonLocal     => "RETURN_NON_LOCAL",
+            Self::Halt                => "HALT",
+            Self::Dup                 => "DUP",
+            Self::PushLocal(_, _)     => "PUSH_LOCAL",
+            Self::PushArgument(_, _)  => "PUSH_ARGUMENT",
+            Self::PushField(_)        => "PUSH_FIELD",
+            Self::PushBlock(_)        => "PUSH_BLOCK",
+            Self::PushConstant(_)     => "PUSH_CONSTANT",
+            Self::PushGlobal(_)       => "PUSH_GLOBAL",
+            Self::PushGlobalCached(_) => "PUSH_GLOBAL_CACHED",
+            Self::Pop                 => "POP",
+            Self::Pop2                => "POP2",
+            Self::PopLocal(_, _)      => "POP_LOCAL",
+            Self::PopArgument(_, _)   => "POP_ARGUMENT",
+            Self::PopField(_)         => "POP_FIELD",
+            Self::NilLocal(_)         => "NIL_LOCAL",
+            Self::Send(_)             => "SEND",
+            Self::SuperSend(_)        => "SUPER_SEND",
+            Self::ReturnLocal         => "RETURN_LOCAL",
+            Self::ReturnNonLocal      => "RETURN_NON_LOCAL",
         }
     }
 
@@ -51,27 +86,30 @@ impl Bytecode {
     pub fn padded_name(self) -> &'static str {
         // PADDED_NAMES[self as usize]
         match self {
-            Self::Halt               => "HALT            ",
-            Self::Dup                => "DUP             ",
-            Self::PushLocal(_, _)    => "PUSH_LOCAL      ",
-            Self::PushArgument(_, _) => "PUSH_ARGUMENT   ",
-            Self::PushField(_)       => "PUSH_FIELD      ",
-            Self::PushBlock(_)       => "PUSH_BLOCK      ",
-            Self::PushConstant(_)    => "PUSH_CONSTANT   ",
-            Self::PushGlobal(_)      => "PUSH_GLOBAL     ",
-            Self::Pop                => "POP             ",
-            Self::PopLocal(_, _)     => "POP_LOCAL       ",
-            Self::PopArgument(_, _)  => "POP_ARGUMENT    ",
-            Self::PopField(_)        => "POP_FIELD       ",
-            Self::Send(_)            => "SEND            ",
-            Self::SuperSend(_)       => "SUPER_SEND      ",
-            Self::ReturnLocal        => "RETURN_LOCAL    ",
-            Self::ReturnNonLocal     => "RETURN_NON_LOCAL",
+            Self::Halt                => "HALT              ",
+            Self::Dup                 => "DUP               ",
+            Self::PushLocal(_, _)     => "PUSH_LOCAL        ",
+            Self::PushArgument(_, _)  => "PUSH_ARGUMENT     ",
+            Self::PushField(_)        => "PUSH_FIELD        ",
+            Self::PushBlock(_)        => "PUSH_BLOCK        ",
+            Self::PushConstant(_)     => "PUSH_CONSTANT     ",
+            Self::PushGlobal(_)       => "PUSH_GLOBAL       ",
+            Self::PushGlobalCached(_) => "PUSH_GLOBAL_CACHED",
+            Self::Pop                 => "POP               ",
+            Self::Pop2                => "POP2              ",
+            Self::PopLocal(_, _)      => "POP_LOCAL         ",
+            Self::PopArgument(_, _)   => "POP_ARGUMENT      ",
+            Self::PopField(_)         => "POP_FIELD         ",
+            Self::NilLocal(_)         => "NIL_LOCAL         ",
+            Self::Send(_)             => "SEND              ",
+            Self::SuperSend(_)        => "SUPER_SEND        ",
+            Self::ReturnLocal         => "RETURN_LOCAL      ",
+            Self::ReturnNonLocal      => "RETURN_NON_LOCAL  ",
         }
     }
 }
 
-pub static NAMES: [&str; 16] = [
+pub static NAMES: [&str; NB_BYTECODES] = [
     "HALT",
     "DUP",
     "PUSH_LOCAL",
@@ -80,33 +118,39 @@ pub static NAMES: [&str; 16] = [
     "PUSH_BLOCK",
     "PUSH_CONSTANT",
     "PUSH_GLOBAL",
+    "PUSH_GLOBAL_CACHED",
     "POP",
+    "POP2",
     "POP_LOCAL",
     "POP_ARGUMENT",
     "POP_FIELD",
+    "NIL_LOCAL",
     "SEND",
     "SUPER_SEND",
     "RETURN_LOCAL",
     "RETURN_NON_LOCAL",
 ];
 
-pub static PADDED_NAMES: [&str; 16] = [
-    "HALT            ",
-    "DUP             ",
-    "PUSH_LOCAL      ",
-    "PUSH_ARGUMENT   ",
-    "PUSH_FIELD      ",
-    "PUSH_BLOCK      ",
-    "PUSH_CONSTANT   ",
-    "PUSH_GLOBAL     ",
-    "POP             ",
-    "POP_LOCAL       ",
-    "POP_ARGUMENT    ",
-    "POP_FIELD       ",
-    "SEND            ",
-    "SUPER_SEND      ",
-    "RETURN_LOCAL    ",
-    "RETURN_NON_LOCAL",
+pub static PADDED_NAMES: [&str; NB_BYTECODES] = [
+    "HALT              ",
+    "DUP               ",
+    "PUSH_LOCAL        ",
+    "PUSH_ARGUMENT     ",
+    "PUSH_FIELD        ",
+    "PUSH_BLOCK        ",
+    "PUSH_CONSTANT     ",
+    "PUSH_GLOBAL       ",
+    "PUSH_GLOBAL_CACHED",
+    "POP               ",
+    "POP2              ",
+    "POP_LOCAL         ",
+    "POP_ARGUMENT      ",
+    "POP_FIELD         ",
+    "NIL_LOCAL         ",
+    "SEND              ",
+    "SUPER_SEND        ",
+    "RETURN_LOCAL      ",
+    "RETURN_NON_LOCAL  ",
 ];
 
 impl fmt::Display for Bytecode {
@@ -121,10 +165,13 @@ impl fmt::Display for Bytecode {
             Self::PushBlock(idx)            => write!(f, "PUSH_BLOCK {}", idx),
             Self::PushConstant(idx)         => write!(f, "PUSH_CONSTANT {}", idx),
             Self::PushGlobal(idx)           => write!(f, "PUSH_GLOBAL {}", idx),
+            Self::PushGlobalCached(idx)     => write!(f, "PUSH_GLOBAL_CACHED {}", idx),
             Self::Pop                       => write!(f, "POP"),
+            Self::Pop2                      => write!(f, "POP2"),
             Self::PopLocal(up_idx, idx)     => write!(f, "POP_LOCAL {}, {}", up_idx, idx),
             Self::PopArgument(up_idx, idx)  => write!(f, "POP_ARGUMENT {}, {}", up_idx, idx),
             Self::PopField(idx)             => write!(f, "POP_FIELD {}", idx),
+            Self::NilLocal(idx)             => write!(f, "NIL_LOCAL {}", idx),
             Self::Send(idx)                 => write!(f, "SEND {}", idx),
             Self::SuperSend(idx)            => write!(f, "SUPER_SEND {}", idx),
             Self::ReturnLocal               => write!(f, "RETURN_LOCAL", ),
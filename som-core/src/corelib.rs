@@ -0,0 +1,81 @@
+/// The source code of the core library classes, by class name.
+#[cfg(feature = "embedded-corelib")]
+static CLASSES: &[(&str, &str)] = &[
+    ("Array", include_str!("../../core-lib/Smalltalk/Array.som")),
+    ("Block", include_str!("../../core-lib/Smalltalk/Block.som")),
+    (
+        "Block1",
+        include_str!("../../core-lib/Smalltalk/Block1.som"),
+    ),
+    (
+        "Block2",
+        include_str!("../../core-lib/Smalltalk/Block2.som"),
+    ),
+    (
+        "Block3",
+        include_str!("../../core-lib/Smalltalk/Block3.som"),
+    ),
+    (
+        "Boolean",
+        include_str!("../../core-lib/Smalltalk/Boolean.som"),
+    ),
+    ("Class", include_str!("../../core-lib/Smalltalk/Class.som")),
+    (
+        "Double",
+        include_str!("../../core-lib/Smalltalk/Double.som"),
+    ),
+    ("False", include_str!("../../core-lib/Smalltalk/False.som")),
+    (
+        "Integer",
+        include_str!("../../core-lib/Smalltalk/Integer.som"),
+    ),
+    (
+        "Metaclass",
+        include_str!("../../core-lib/Smalltalk/Metaclass.som"),
+    ),
+    (
+        "Method",
+        include_str!("../../core-lib/Smalltalk/Method.som"),
+    ),
+    ("Nil", include_str!("../../core-lib/Smalltalk/Nil.som")),
+    (
+        "Object",
+        include_str!("../../core-lib/Smalltalk/Object.som"),
+    ),
+    (
+        "Primitive",
+        include_str!("../../core-lib/Smalltalk/Primitive.som"),
+    ),
+    (
+        "String",
+        include_str!("../../core-lib/Smalltalk/String.som"),
+    ),
+    (
+        "Symbol",
+        include_str!("../../core-lib/Smalltalk/Symbol.som"),
+    ),
+    (
+        "System",
+        include_str!("../../core-lib/Smalltalk/System.som"),
+    ),
+    ("True", include_str!("../../core-lib/Smalltalk/True.som")),
+];
+
+/// Get the embedded source code of a core library class.
+///
+/// This always returns `None` unless the `embedded-corelib` feature is enabled.
+#[cfg(feature = "embedded-corelib")]
+pub fn lookup(class_name: &str) -> Option<&'static str> {
+    CLASSES
+        .iter()
+        .find(|(name, _)| *name == class_name)
+        .map(|(_, source)| *source)
+}
+
+/// Get the embedded source code of a core library class.
+///
+/// This always returns `None` unless the `embedded-corelib` feature is enabled.
+#[cfg(not(feature = "embedded-corelib"))]
+pub fn lookup(_: &str) -> Option<&'static str> {
+    None
+}
@@ -6,3 +6,5 @@
 pub mod ast;
 /// The SOM bytecode definitions.
 pub mod bytecode;
+/// The SOM standard library, optionally embedded into the binary.
+pub mod corelib;
@@ -7,6 +7,7 @@ use indexmap::IndexMap;
 use som_core::ast::{ClassDef, MethodBody};
 
 use crate::method::{Method, MethodKind};
+use crate::primitives;
 use crate::value::Value;
 use crate::{SOMRef, SOMWeakRef};
 
@@ -35,6 +36,8 @@ pub struct Class {
     pub methods: IndexMap<String, Rc<Method>>,
     /// Is this class a static one ?
     pub is_static: bool,
+    /// The comment documenting this class, if any.
+    pub comment: Option<String>,
 }
 
 impl Class {
@@ -73,6 +76,7 @@ impl Class {
             locals: static_locals,
             methods: IndexMap::new(),
             is_static: true,
+            comment: None,
         }));
 
         let instance_class = Rc::new(RefCell::new(Self {
@@ -82,6 +86,7 @@ impl Class {
             locals: instance_locals,
             methods: IndexMap::new(),
             is_static: false,
+            comment: defn.comment.clone(),
         }));
 
         let static_methods = defn
@@ -105,7 +110,7 @@ impl Class {
             })
             .collect();
 
-        let instance_methods = defn
+        let mut instance_methods: IndexMap<String, Rc<Method>> = defn
             .instance_methods
             .iter()
             .map(|method| {
@@ -126,12 +131,62 @@ impl Class {
             })
             .collect();
 
+        for signature in primitives::get_extensions(defn.name.as_str()) {
+            if instance_methods.contains_key(*signature) {
+                continue;
+            }
+            let method = Method {
+                kind: MethodKind::primitive_from_signature(defn.name.as_str(), signature),
+                signature: signature.to_string(),
+                holder: Rc::downgrade(&instance_class),
+            };
+            instance_methods.insert(signature.to_string(), Rc::new(method));
+        }
+
+        for signature in primitives::get_overrides(defn.name.as_str()) {
+            let method = Method {
+                kind: MethodKind::primitive_from_signature(defn.name.as_str(), signature),
+                signature: signature.to_string(),
+                holder: Rc::downgrade(&instance_class),
+            };
+            instance_methods.insert(signature.to_string(), Rc::new(method));
+        }
+
         static_class.borrow_mut().methods = static_methods;
         instance_class.borrow_mut().methods = instance_methods;
 
         Ok(instance_class)
     }
 
+    /// Add the methods of a class extension to the given (already loaded) class,
+    /// replacing the methods it already has with the same signatures.
+    pub fn install_methods(this: &SOMRef<Self>, defn: &ClassDef) {
+        let targets = [
+            (this.clone(), &defn.instance_methods),
+            (this.borrow().class(), &defn.static_methods),
+        ];
+        for (class, methods) in targets.iter() {
+            for method in methods.iter() {
+                let kind = match method.body {
+                    MethodBody::Primitive => MethodKind::primitive_from_signature(
+                        defn.name.as_str(),
+                        method.signature.as_str(),
+                    ),
+                    MethodBody::Body { .. } => MethodKind::Defined(method.clone()),
+                };
+                let method = Method {
+                    kind,
+                    signature: method.signature.clone(),
+                    holder: Rc::downgrade(class),
+                };
+                class
+                    .borrow_mut()
+                    .methods
+                    .insert(method.signature.clone(), Rc::new(method));
+            }
+        }
+    }
+
     /// Get the class' name.
     pub fn name(&self) -> &str {
         self.name.as_str()
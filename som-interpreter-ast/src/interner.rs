@@ -7,6 +7,8 @@
 //!
 
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
 use std::mem;
 
 /// An interned string.
@@ -56,11 +58,73 @@ impl Interner {
         id
     }
 
+    /// Intern a batch of strings, returning their IDs in the same order.
+    pub fn intern_all<S: AsRef<str>>(
+        &mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> Vec<Interned> {
+        let names = names.into_iter();
+        self.map.reserve(names.size_hint().0);
+        names.map(|name| self.intern(name.as_ref())).collect()
+    }
+
     /// Get the string associated to a given interning ID.
     pub fn lookup(&self, id: Interned) -> &str {
         self.vec[id.0 as usize]
     }
 
+    /// Write all the interned strings, in ID order, to the given writer.
+    ///
+    /// The format is a little-endian `u32` count, followed by each string as a little-endian `u32` length and its UTF-8 bytes.
+    pub fn save_to(&self, mut writer: impl Write) -> io::Result<()> {
+        fn write_len(writer: &mut impl Write, len: usize) -> io::Result<()> {
+            let len = u32::try_from(len)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+            writer.write_all(&len.to_le_bytes())
+        }
+
+        write_len(&mut writer, self.vec.len())?;
+        for name in &self.vec {
+            write_len(&mut writer, name.len())?;
+            writer.write_all(name.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read an interner previously written with [`Interner::save_to`], with the same ID assignments.
+    pub fn load_from(mut reader: impl Read) -> io::Result<Self> {
+        fn read_len(reader: &mut impl Read) -> io::Result<usize> {
+            let mut bytes = [0; 4];
+            reader.read_exact(&mut bytes)?;
+            Ok(u32::from_le_bytes(bytes) as usize)
+        }
+
+        // The counts and lengths come from the input, so memory is only reserved for what is actually read.
+        let count = read_len(&mut reader)?;
+        let mut interner = Self::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let len = read_len(&mut reader)?;
+            let mut bytes = Vec::new();
+            reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+            if bytes.len() != len {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "truncated interned string",
+                ));
+            }
+            let name = String::from_utf8(bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            if interner.map.contains_key(name.as_str()) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("duplicated interned string: '{}'", name),
+                ));
+            }
+            interner.intern(name.as_str());
+        }
+        Ok(interner)
+    }
+
     unsafe fn alloc(&mut self, name: &str) -> &'static str {
         let cap = self.buf.capacity();
         if cap < self.buf.len() + name.len() {
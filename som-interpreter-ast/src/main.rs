@@ -3,15 +3,19 @@
 //!
 #![warn(missing_docs)]
 
+use std::cell::RefCell;
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use anyhow::anyhow;
 use structopt::StructOpt;
 
 mod shell;
 
-use som_interpreter_ast::invokable::Return;
+use som_interpreter_ast::instance::Instance;
+use som_interpreter_ast::invokable::{Invoke, Return};
 use som_interpreter_ast::universe::Universe;
 use som_interpreter_ast::value::Value;
 
@@ -32,14 +36,69 @@ struct Options {
     /// Enable verbose output (with timing information).
     #[structopt(short = "v")]
     verbose: bool,
+
+    /// Warn about primitives declared by classes but not implemented by the interpreter.
+    #[structopt(long)]
+    warn_missing_primitives: bool,
+
+    /// Suppress the interpreter's own diagnostics (the program's output is unaffected).
+    #[structopt(short, long)]
+    quiet: bool,
+
+    /// Run this (unary) method on an instance of the loaded class, instead of going through `System>>#initialize:`.
+    #[structopt(long)]
+    entry: Option<String>,
+
+    /// Allow SOM programs to read environment variables (through `System>>#environmentVariable:`).
+    #[structopt(long, parse(try_from_str), default_value = "true")]
+    allow_env: bool,
+
+    /// Run the named benchmark class (`benchmark`, then `verifyResult:`) in a loop and report its iterations per second.
+    #[structopt(long)]
+    bench: Option<String>,
+
+    /// For how long (in milliseconds) to run the benchmark given with `--bench`.
+    #[structopt(long, default_value = "1000")]
+    bench_duration: u64,
+
+    /// Load (and compile) the named class before running the program (can be repeated).
+    #[structopt(long)]
+    preload: Vec<String>,
+
+    /// Print every primitive bound by the core classes, instead of running anything.
+    #[structopt(long)]
+    list_primitives: bool,
+
+    /// Report unresolved globals (which are not classes in the classpath) as errors, instead of handing them to `unknownGlobal:`.
+    #[structopt(long)]
+    strict_globals: bool,
 }
 
 fn main() -> anyhow::Result<()> {
     let opts: Options = Options::from_args();
 
+    som_interpreter_ast::set_quiet(opts.quiet);
+    som_interpreter_ast::primitives::set_warn_missing_primitives(opts.warn_missing_primitives);
+    som_interpreter_ast::primitives::set_allow_environment(opts.allow_env);
+
+    if opts.list_primitives {
+        let universe = Universe::with_classpath(opts.classpath)?;
+        return list_primitives(&universe);
+    }
+
+    if let Some(bench) = opts.bench {
+        let mut universe = Universe::with_classpath(opts.classpath)?;
+        universe.strict_globals = opts.strict_globals;
+        preload_classes(&mut universe, &opts.preload)?;
+        let duration = Duration::from_millis(opts.bench_duration);
+        return run_benchmark(&mut universe, bench.as_str(), duration);
+    }
+
     match opts.file {
         None => {
             let mut universe = Universe::with_classpath(opts.classpath)?;
+            universe.strict_globals = opts.strict_globals;
+            preload_classes(&mut universe, &opts.preload)?;
             shell::interactive(&mut universe, opts.verbose)?
         }
         Some(file) => {
@@ -56,23 +115,38 @@ fn main() -> anyhow::Result<()> {
             }
 
             let mut universe = Universe::with_classpath(classpath)?;
+            universe.strict_globals = opts.strict_globals;
+            preload_classes(&mut universe, &opts.preload)?;
 
-            let args = std::iter::once(String::from(file_stem))
-                .chain(opts.args.iter().cloned())
-                .map(Rc::new)
-                .map(Value::String)
-                .collect();
+            let output = match opts.entry {
+                Some(entry) => {
+                    if !entry.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+                        return Err(anyhow!("the entry point '{}' is not a unary method", entry));
+                    }
 
-            let output = universe.initialize(args).unwrap_or_else(|| {
-                Return::Exception(format!("could not find 'System>>#initialize:'"))
-            });
+                    let class = universe.load_class(file_stem)?;
+                    let instance = Instance::from_class(class);
+                    let instance = Value::Instance(Rc::new(RefCell::new(instance)));
 
-            // let class = universe.load_class_from_path(file)?;
-            // let instance = Instance::from_class(class);
-            // let instance = Value::Instance(Rc::new(RefCell::new(instance)));
+                    let invokable = instance
+                        .lookup_method(&universe, entry.as_str())
+                        .ok_or_else(|| {
+                            anyhow!("could not find method '{}>>#{}'", file_stem, entry)
+                        })?;
+                    invokable.invoke(&mut universe, vec![instance])
+                }
+                None => {
+                    let args = std::iter::once(String::from(file_stem))
+                        .chain(opts.args.iter().cloned())
+                        .map(Rc::new)
+                        .map(Value::String)
+                        .collect();
 
-            // let invokable = instance.lookup_method(&universe, "run").unwrap();
-            // let output = invokable.invoke(&mut universe, vec![instance]);
+                    universe.initialize(args).unwrap_or_else(|| {
+                        Return::Exception(format!("could not find 'System>>#initialize:'"))
+                    })
+                }
+            };
 
             match output {
                 Return::Exception(message) => println!("ERROR: {}", message),
@@ -84,3 +158,119 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+/// Prints every primitive bound by the core classes, one `Class >> signature (side)` per line.
+fn list_primitives(universe: &Universe) -> anyhow::Result<()> {
+    let core = &universe.core;
+    let classes = [
+        &core.object_class,
+        &core.class_class,
+        &core.metaclass_class,
+        &core.nil_class,
+        &core.integer_class,
+        &core.double_class,
+        &core.array_class,
+        &core.method_class,
+        &core.primitive_class,
+        &core.symbol_class,
+        &core.string_class,
+        &core.system_class,
+        &core.block_class,
+        &core.block1_class,
+        &core.block2_class,
+        &core.block3_class,
+        &core.boolean_class,
+        &core.true_class,
+        &core.false_class,
+    ];
+
+    let stdout = io::stdout();
+    let mut output = stdout.lock();
+    for class in classes.iter() {
+        let name = class.borrow().name().to_string();
+        let metaclass = class.borrow().class();
+        for (class, side) in [(*class, "instance-side"), (&metaclass, "class-side")].iter() {
+            for method in class.borrow().methods.values() {
+                if method.is_primitive() {
+                    writeln!(output, "{} >> {} ({})", name, method.signature(), side)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Loads each of the given classes, so that they are compiled before the program starts running.
+fn preload_classes(universe: &mut Universe, class_names: &[String]) -> anyhow::Result<()> {
+    for class_name in class_names {
+        universe
+            .load_class(class_name.as_str())
+            .map_err(|err| anyhow!("could not preload class '{}': {}", class_name, err))?;
+    }
+    Ok(())
+}
+
+/// Repeatedly runs `benchmark` (and checks its result with `verifyResult:`) on an instance of the given class,
+/// until the given duration has elapsed, and then reports the achieved rate in a machine-parseable line.
+fn run_benchmark(
+    universe: &mut Universe,
+    class_name: &str,
+    duration: Duration,
+) -> anyhow::Result<()> {
+    let class = universe.load_class(class_name)?;
+    let instance = Instance::from_class(class);
+    let instance = Value::Instance(Rc::new(RefCell::new(instance)));
+
+    let start = Instant::now();
+    let mut iterations: u64 = 0;
+    loop {
+        let result = invoke_method(universe, &instance, "benchmark", Vec::new())?;
+        let verified = invoke_method(universe, &instance, "verifyResult:", vec![result])?;
+        if verified != Value::Boolean(true) {
+            return Err(anyhow!(
+                "the benchmark '{}' produced an incorrect result",
+                class_name
+            ));
+        }
+        iterations += 1;
+
+        if start.elapsed() >= duration {
+            break;
+        }
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "{}: iterations={} elapsed_us={} iterations_per_sec={:.2}",
+        class_name,
+        iterations,
+        elapsed.as_micros(),
+        iterations as f64 / elapsed.as_secs_f64(),
+    );
+
+    Ok(())
+}
+
+/// Invokes a method on the given receiver, returning its result.
+fn invoke_method(
+    universe: &mut Universe,
+    receiver: &Value,
+    signature: &str,
+    args: Vec<Value>,
+) -> anyhow::Result<Value> {
+    let class_name = receiver.class(universe).borrow().name().to_string();
+    let invokable = receiver
+        .lookup_method(universe, signature)
+        .ok_or_else(|| anyhow!("could not find method '{}>>#{}'", class_name, signature))?;
+
+    let args = std::iter::once(receiver.clone()).chain(args).collect();
+    match invokable.invoke(universe, args) {
+        Return::Local(value) => Ok(value),
+        Return::Exception(message) => Err(anyhow!("{}", message)),
+        Return::NonLocal(..) | Return::Restart => Err(anyhow!(
+            "'{}>>#{}' returned unexpectedly",
+            class_name,
+            signature
+        )),
+    }
+}
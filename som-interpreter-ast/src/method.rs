@@ -49,6 +49,12 @@ impl MethodKind {
         //     primitive.is_some()
         // );
         primitive.map(MethodKind::Primitive).unwrap_or_else(|| {
+            if primitives::warn_missing_primitives() {
+                eprintln!(
+                    "warning: primitive '{}>>#{}' is declared but not implemented",
+                    class_name, signature
+                );
+            }
             MethodKind::NotImplemented(format!("{}>>#{}", class_name, signature))
         })
         // .unwrap_or_else(|| panic!("unimplemented primitive: '{}>>#{}'", class_name, signature))
@@ -1,9 +1,16 @@
 use std::cell::RefCell;
 use std::convert::TryFrom;
+use std::ops::Range;
 use std::rc::Rc;
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::block::Block;
 use crate::expect_args;
-use crate::invokable::Return;
+use crate::frame::FrameKind;
+use crate::invokable::{Invoke, Return};
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
 use crate::value::Value;
@@ -13,12 +20,12 @@ fn at(_: &mut Universe, args: Vec<Value>) -> Return {
 
     expect_args!(SIGNATURE, args, [
         Value::Array(values) => values,
-        Value::Integer(index) => index,
+        index => index,
     ]);
 
-    let index = match usize::try_from(index - 1) {
-        Ok(index) => index,
-        Err(err) => return Return::Exception(format!("'{}': {}", SIGNATURE, err)),
+    let index = match index.as_index().and_then(|index| index.checked_sub(1)) {
+        Some(index) => index,
+        None => return Return::Exception(format!("'{}': invalid index", SIGNATURE)),
     };
     let value = values.borrow().get(index).cloned().unwrap_or(Value::Nil);
     Return::Local(value)
@@ -29,13 +36,13 @@ fn at_put(_: &mut Universe, args: Vec<Value>) -> Return {
 
     expect_args!(SIGNATURE, args, [
         Value::Array(values) => values,
-        Value::Integer(index) => index,
+        index => index,
         value => value,
     ]);
 
-    let index = match usize::try_from(index - 1) {
-        Ok(index) => index,
-        Err(err) => return Return::Exception(format!("'{}': {}", SIGNATURE, err)),
+    let index = match index.as_index().and_then(|index| index.checked_sub(1)) {
+        Some(index) => index,
+        None => return Return::Exception(format!("'{}': invalid index", SIGNATURE)),
     };
     if let Some(location) = values.borrow_mut().get_mut(index) {
         *location = value;
@@ -43,6 +50,30 @@ fn at_put(_: &mut Universe, args: Vec<Value>) -> Return {
     Return::Local(Value::Array(values))
 }
 
+fn at_if_absent(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#at:ifAbsent:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        index => index,
+        Value::Block(block) => block,
+    ]);
+
+    let value = index
+        .as_index()
+        .and_then(|index| index.checked_sub(1))
+        .and_then(|index| values.borrow().get(index).cloned());
+    match value {
+        Some(value) => Return::Local(value),
+        None => universe.with_frame(
+            FrameKind::Block {
+                block: block.clone(),
+            },
+            |universe| block.invoke(universe, vec![Value::Block(block.clone())]),
+        ),
+    }
+}
+
 fn length(_: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "Array>>#length";
 
@@ -74,6 +105,369 @@ fn new(_: &mut Universe, args: Vec<Value>) -> Return {
     }
 }
 
+/// Invokes the block on each element of a snapshot of the array, since the block may modify the array,
+/// passing the element's (1-based) index after it if `with_index` is set.
+///
+/// Each element is handed to `f` along with the block's result. The iteration stops at the first error
+/// from `f`, or at the first non-local return or exception from the block, which is returned.
+fn invoke_on_snapshot(
+    universe: &mut Universe,
+    signature: &str,
+    values: &RefCell<Vec<Value>>,
+    block: &Rc<Block>,
+    with_index: bool,
+    mut f: impl FnMut(Value, Value) -> Result<(), Return>,
+) -> Result<(), Return> {
+    let elements = values.borrow().clone();
+    for (idx, element) in elements.into_iter().enumerate() {
+        let mut block_args = vec![Value::Block(block.clone()), element.clone()];
+        if with_index {
+            let index = i64::try_from(idx + 1)
+                .map_err(|err| Return::Exception(format!("'{}': {}", signature, err)))?;
+            block_args.push(Value::Integer(index));
+        }
+        match universe.with_frame(
+            FrameKind::Block {
+                block: block.clone(),
+            },
+            |universe| block.invoke(universe, block_args),
+        ) {
+            Return::Local(result) => f(element, result)?,
+            ret => return Err(ret),
+        }
+    }
+
+    Ok(())
+}
+
+fn group_by(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#groupBy:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Block(block) => block,
+    ]);
+
+    let mut groups: Vec<(Value, Vec<Value>)> = Vec::new();
+    let grouped = invoke_on_snapshot(universe, SIGNATURE, &values, &block, false, |value, key| {
+        match groups.iter_mut().find(|(group_key, _)| *group_key == key) {
+            Some((_, elements)) => elements.push(value),
+            None => groups.push((key, vec![value])),
+        }
+        Ok(())
+    });
+    if let Err(ret) = grouped {
+        return ret;
+    }
+
+    let groups = groups
+        .into_iter()
+        .map(|(key, elements)| {
+            let elements = Value::Array(Rc::new(RefCell::new(elements)));
+            Value::Array(Rc::new(RefCell::new(vec![key, elements])))
+        })
+        .collect();
+
+    Return::Local(Value::Array(Rc::new(RefCell::new(groups))))
+}
+
+fn partition(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#partition:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Block(block) => block,
+    ]);
+
+    let mut matching = Vec::new();
+    let mut rest = Vec::new();
+    let partitioned = invoke_on_snapshot(
+        universe,
+        SIGNATURE,
+        &values,
+        &block,
+        false,
+        |value, outcome| {
+            match outcome {
+                Value::Boolean(true) => matching.push(value),
+                Value::Boolean(false) => rest.push(value),
+                _ => {
+                    return Err(Return::Exception(format!(
+                        "'{}': the block did not return a boolean",
+                        SIGNATURE
+                    )))
+                }
+            }
+            Ok(())
+        },
+    );
+    if let Err(ret) = partitioned {
+        return ret;
+    }
+
+    let partitions = vec![
+        Value::Array(Rc::new(RefCell::new(matching))),
+        Value::Array(Rc::new(RefCell::new(rest))),
+    ];
+    Return::Local(Value::Array(Rc::new(RefCell::new(partitions))))
+}
+
+fn do_with_index(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#doWithIndex:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Block(block) => block,
+    ]);
+
+    let done = invoke_on_snapshot(universe, SIGNATURE, &values, &block, true, |_, _| Ok(()));
+    if let Err(ret) = done {
+        return ret;
+    }
+
+    Return::Local(Value::Array(values))
+}
+
+fn with_index_collect(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#withIndexCollect:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Block(block) => block,
+    ]);
+
+    let mut results = Vec::with_capacity(values.borrow().len());
+    let collected = invoke_on_snapshot(universe, SIGNATURE, &values, &block, true, |_, result| {
+        results.push(result);
+        Ok(())
+    });
+    if let Err(ret) = collected {
+        return ret;
+    }
+
+    Return::Local(Value::Array(Rc::new(RefCell::new(results))))
+}
+
+fn all_satisfy(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#allSatisfy:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Block(block) => block,
+    ]);
+
+    let elements = values.borrow().clone();
+    match find_with_outcome(universe, SIGNATURE, elements, &block, false) {
+        Return::Local(Value::Boolean(found)) => Return::Local(Value::Boolean(!found)),
+        ret => ret,
+    }
+}
+
+fn any_satisfy(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#anySatisfy:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Block(block) => block,
+    ]);
+
+    let elements = values.borrow().clone();
+    find_with_outcome(universe, SIGNATURE, elements, &block, true)
+}
+
+/// Evaluates the predicate block on each element in turn, stopping at the first one for which it yields `outcome`,
+/// and returns whether such an element was found.
+fn find_with_outcome(
+    universe: &mut Universe,
+    signature: &str,
+    elements: Vec<Value>,
+    block: &Rc<Block>,
+    outcome: bool,
+) -> Return {
+    for element in elements {
+        let block_args = vec![Value::Block(block.clone()), element];
+        match universe.with_frame(
+            FrameKind::Block {
+                block: block.clone(),
+            },
+            |universe| block.invoke(universe, block_args),
+        ) {
+            Return::Local(Value::Boolean(value)) if value == outcome => {
+                return Return::Local(Value::Boolean(true));
+            }
+            Return::Local(Value::Boolean(_)) => {}
+            Return::Local(_) => {
+                return Return::Exception(format!(
+                    "'{}': the block did not return a boolean",
+                    signature
+                ));
+            }
+            ret => return ret,
+        }
+    }
+
+    Return::Local(Value::Boolean(false))
+}
+
+fn first(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#first:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Integer(count) => count,
+    ]);
+
+    let values = values.borrow();
+    let count = usize::try_from(count).unwrap_or(0).min(values.len());
+    let prefix = values[..count].to_vec();
+    Return::Local(Value::Array(Rc::new(RefCell::new(prefix))))
+}
+
+fn last(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#last:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Integer(count) => count,
+    ]);
+
+    let values = values.borrow();
+    let count = usize::try_from(count).unwrap_or(0).min(values.len());
+    let suffix = values[values.len() - count..].to_vec();
+    Return::Local(Value::Array(Rc::new(RefCell::new(suffix))))
+}
+
+fn occurrences_of(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#occurrencesOf:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        value => value,
+    ]);
+
+    let count = values.borrow().iter().filter(|it| **it == value).count();
+    match i64::try_from(count) {
+        Ok(count) => Return::Local(Value::Integer(count)),
+        Err(err) => Return::Exception(format!("'{}': {}", SIGNATURE, err)),
+    }
+}
+
+fn remove_all(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#removeAll:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        value => value,
+    ]);
+
+    let remaining: Vec<_> = values
+        .borrow()
+        .iter()
+        .filter(|it| **it != value)
+        .cloned()
+        .collect();
+    Return::Local(Value::Array(Rc::new(RefCell::new(remaining))))
+}
+
+fn replace_from_to_with(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#replaceFrom:to:with:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Integer(start) => start,
+        Value::Integer(end) => end,
+        Value::Array(source) => source,
+    ]);
+
+    let spliced = splice_range(&values.borrow(), start, end, &source.borrow());
+    match spliced {
+        Some((range, replacement)) => {
+            values.borrow_mut()[range].clone_from_slice(&replacement);
+            Return::Local(Value::Array(values))
+        }
+        None => Return::Local(Value::Nil),
+    }
+}
+
+/// Resolve the inclusive (1-based) range of `values` to replace with the first elements of `source`.
+///
+/// Returns `None` if the range is out of bounds or if `source` is too short for it.
+fn splice_range(
+    values: &[Value],
+    start: i64,
+    end: i64,
+    source: &[Value],
+) -> Option<(Range<usize>, Vec<Value>)> {
+    let start = usize::try_from(start).ok()?.checked_sub(1)?;
+    let end = usize::try_from(end).ok()?;
+    if start > end || end > values.len() {
+        return None;
+    }
+    // The source is copied, since it may well be the receiver itself.
+    let replacement = source.get(..end - start)?.to_vec();
+    Some((start..end, replacement))
+}
+
+fn shuffle(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#shuffle:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+        Value::Integer(seed) => seed,
+    ]);
+
+    let shuffled = shuffled_with_seed(&values.borrow(), seed);
+    Return::Local(Value::Array(Rc::new(RefCell::new(shuffled))))
+}
+
+/// Copy the given values in a (Fisher-Yates) shuffled order, which only depends on the given seed.
+fn shuffled_with_seed(values: &[Value], seed: i64) -> Vec<Value> {
+    let mut shuffled = values.to_vec();
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    shuffled.shuffle(&mut rng);
+    shuffled
+}
+
+fn remove_duplicates(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Array>>#removeDuplicates";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Array(values) => values,
+    ]);
+
+    let unique = without_duplicates(&values.borrow());
+    Return::Local(Value::Array(Rc::new(RefCell::new(unique))))
+}
+
+/// Copy the given values, keeping only the first occurrence of each of them (in their original order).
+fn without_duplicates(values: &[Value]) -> Vec<Value> {
+    let mut unique: Vec<Value> = Vec::with_capacity(values.len());
+    for value in values {
+        if !unique.contains(value) {
+            unique.push(value.clone());
+        }
+    }
+    unique
+}
+
+/// The primitives this interpreter provides for the **Array** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "groupBy:",
+    "doWithIndex:",
+    "first:",
+    "last:",
+    "occurrencesOf:",
+    "allSatisfy:",
+    "anySatisfy:",
+    "withIndexCollect:",
+    "at:ifAbsent:",
+    "removeAll:",
+    "replaceFrom:to:with:",
+    "shuffle:",
+    "partition:",
+    "removeDuplicates",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -81,6 +475,20 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "at:put:" => Some(self::at_put),
         "length" => Some(self::length),
         "new:" => Some(self::new),
+        "groupBy:" => Some(self::group_by),
+        "doWithIndex:" => Some(self::do_with_index),
+        "first:" => Some(self::first),
+        "last:" => Some(self::last),
+        "occurrencesOf:" => Some(self::occurrences_of),
+        "allSatisfy:" => Some(self::all_satisfy),
+        "anySatisfy:" => Some(self::any_satisfy),
+        "withIndexCollect:" => Some(self::with_index_collect),
+        "at:ifAbsent:" => Some(self::at_if_absent),
+        "removeAll:" => Some(self::remove_all),
+        "replaceFrom:to:with:" => Some(self::replace_from_to_with),
+        "shuffle:" => Some(self::shuffle),
+        "partition:" => Some(self::partition),
+        "removeDuplicates" => Some(self::remove_duplicates),
         _ => None,
     }
 }
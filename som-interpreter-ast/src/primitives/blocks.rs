@@ -1,3 +1,6 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
 use crate::expect_args;
 use crate::frame::FrameKind;
 use crate::invokable::Invoke;
@@ -26,19 +29,56 @@ pub mod block1 {
         )
     }
 
-    fn restart(_: &mut Universe, args: Vec<Value>) -> Return {
+    fn restart(universe: &mut Universe, args: Vec<Value>) -> Return {
         const SIGNATURE: &str = "Block>>#restart";
 
         expect_args!(SIGNATURE, args, [Value::Block(_)]);
 
-        Return::Restart
+        // Restarting is how loops iterate, so this is where timed blocks get interrupted.
+        match universe.expired_timeout() {
+            Some(budget) => Return::Exception(format!(
+                "block exceeded its time budget of {} ms",
+                budget.as_millis()
+            )),
+            None => Return::Restart,
+        }
+    }
+
+    fn value_with_timeout(universe: &mut Universe, args: Vec<Value>) -> Return {
+        const SIGNATURE: &str = "Block>>#valueWithTimeout:";
+
+        expect_args!(SIGNATURE, args, [
+            Value::Block(block) => block,
+            Value::Integer(millis) => millis,
+        ]);
+
+        if block.nb_parameters() != 0 {
+            return Return::Exception(format!(
+                "'{}': the block should not expect any argument",
+                SIGNATURE
+            ));
+        }
+
+        let budget = Duration::from_millis(u64::try_from(millis).unwrap_or(0));
+        universe.with_timeout(budget, |universe| {
+            universe.with_frame(
+                FrameKind::Block {
+                    block: block.clone(),
+                },
+                |universe| block.invoke(universe, vec![Value::Block(block.clone())]),
+            )
+        })
     }
 
+    /// The primitives this interpreter provides for the **Block** class, beyond those declared by the core library.
+    pub static EXTENSIONS: &[&str] = &["valueWithTimeout:"];
+
     /// Search for a primitive matching the given signature.
     pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         match signature.as_ref() {
             "value" => Some(self::value),
             "restart" => Some(self::restart),
+            "valueWithTimeout:" => Some(self::value_with_timeout),
             _ => None,
         }
     }
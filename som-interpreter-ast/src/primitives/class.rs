@@ -68,26 +68,66 @@ fn fields(universe: &mut Universe, args: Vec<Value>) -> Return {
         Value::Class(class) => class,
     ]);
 
-    fn gather_locals(universe: &mut Universe, class: SOMRef<Class>) -> Vec<Value> {
-        let mut fields = match class.borrow().super_class() {
-            Some(super_class) => gather_locals(universe, super_class),
-            None => Vec::new(),
-        };
-        fields.extend(
-            class
-                .borrow()
-                .locals
-                .keys()
-                .map(|field| Value::Symbol(universe.intern_symbol(field))),
-        );
-        fields
-    }
-
     let fields = gather_locals(universe, class);
 
     Return::Local(Value::Array(Rc::new(RefCell::new(fields))))
 }
 
+fn all_inst_var_names(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Class>>#allInstVarNames";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Class(class) => class,
+    ]);
+
+    let names = gather_locals(universe, class);
+
+    Return::Local(Value::Array(Rc::new(RefCell::new(names))))
+}
+
+fn comment(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Class>>#comment";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Class(class) => class,
+    ]);
+
+    let comment = class.borrow().comment.clone().unwrap_or_default();
+    Return::Local(Value::String(Rc::new(comment)))
+}
+
+fn includes_behavior(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Class>>#includesBehavior:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Class(class) => class,
+        Value::Class(other) => other,
+    ]);
+
+    let includes = std::iter::successors(Some(class), |class| class.borrow().super_class())
+        .any(|class| Rc::ptr_eq(&class, &other));
+    Return::Local(Value::Boolean(includes))
+}
+
+/// Collect the names of the fields of a class (including inherited ones), in field-layout order.
+fn gather_locals(universe: &mut Universe, class: SOMRef<Class>) -> Vec<Value> {
+    let mut fields = match class.borrow().super_class() {
+        Some(super_class) => gather_locals(universe, super_class),
+        None => Vec::new(),
+    };
+    fields.extend(
+        class
+            .borrow()
+            .locals
+            .keys()
+            .map(|field| Value::Symbol(universe.intern_symbol(field))),
+    );
+    fields
+}
+
+/// The primitives this interpreter provides for the **Class** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &["allInstVarNames", "comment", "includesBehavior:"];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -96,6 +136,9 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "fields" => Some(self::fields),
         "methods" => Some(self::methods),
         "superclass" => Some(self::superclass),
+        "allInstVarNames" => Some(self::all_inst_var_names),
+        "comment" => Some(self::comment),
+        "includesBehavior:" => Some(self::includes_behavior),
         _ => None,
     }
 }
@@ -1,5 +1,8 @@
+use std::convert::TryFrom;
 use std::rc::Rc;
 
+use num_traits::ToPrimitive;
+
 use crate::expect_args;
 use crate::invokable::Return;
 use crate::primitives::PrimitiveFn;
@@ -47,6 +50,21 @@ fn as_string(_: &mut Universe, args: Vec<Value>) -> Return {
     Return::Local(Value::String(Rc::new(value.to_string())))
 }
 
+fn as_string_with_decimals(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#asStringWithDecimals:";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+        Value::Integer(decimals) => decimals,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    Return::Local(Value::String(Rc::new(format_with_decimals(
+        value, decimals,
+    ))))
+}
+
 fn as_integer(_: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "Double>>#asInteger";
 
@@ -57,6 +75,30 @@ fn as_integer(_: &mut Universe, args: Vec<Value>) -> Return {
     Return::Local(Value::Integer(value.trunc() as i64))
 }
 
+fn abs(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#abs";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    Return::Local(Value::Double(value.abs()))
+}
+
+fn negated(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#negated";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    Return::Local(Value::Double(-value))
+}
+
 fn sqrt(_: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "Double>>#sqrt";
 
@@ -105,16 +147,73 @@ fn sin(_: &mut Universe, args: Vec<Value>) -> Return {
     Return::Local(Value::Double(value.sin()))
 }
 
+fn arc_sin(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#arcSin";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    Return::Local(Value::Double(value.asin()))
+}
+
+fn arc_cos(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#arcCos";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    Return::Local(Value::Double(value.acos()))
+}
+
+fn arc_tan(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#arcTan";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    Return::Local(Value::Double(value.atan()))
+}
+
+fn arc_tan2(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#arcTan:";
+
+    expect_args!(SIGNATURE, args, [
+        y => y,
+        x => x,
+    ]);
+
+    let y = promote!(SIGNATURE, y);
+    let x = promote!(SIGNATURE, x);
+
+    Return::Local(Value::Double(y.atan2(x)))
+}
+
 fn eq(_: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "Double>>#=";
 
     expect_args!(SIGNATURE, args, [
-        // Value::Double(a) => a,
-        // Value::Double(b) => b,
         a => a,
         b => b,
     ]);
 
+    // Anything that is not a number is never equal, and neither is NaN (not even to itself).
+    let a = promote!(SIGNATURE, a);
+    let b = match b {
+        Value::Integer(b) => b as f64,
+        Value::Double(b) => b,
+        Value::BigInteger(b) => b.to_f64().unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    };
+
     Return::Local(Value::Boolean(a == b))
 }
 
@@ -208,6 +307,67 @@ fn positive_infinity(_: &mut Universe, _: Vec<Value>) -> Return {
     Return::Local(Value::Double(f64::INFINITY))
 }
 
+fn is_nan(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#isNaN";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Double(value) => value,
+    ]);
+
+    Return::Local(Value::Boolean(value.is_nan()))
+}
+
+fn is_infinite(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#isInfinite";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Double(value) => value,
+    ]);
+
+    Return::Local(Value::Boolean(value.is_infinite()))
+}
+
+fn is_finite(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Double>>#isFinite";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Double(value) => value,
+    ]);
+
+    Return::Local(Value::Boolean(value.is_finite()))
+}
+
+/// The most decimals a double is formatted with, which is enough to write any `f64` exactly
+/// (the smallest subnormal, `2^-1074`, has that many).
+const MAX_DECIMALS: usize = 1074;
+
+/// Format a double with a fixed number of decimals (a negative count being treated as zero, and a larger one than
+/// `MAX_DECIMALS` as that maximum).
+fn format_with_decimals(value: f64, decimals: i64) -> String {
+    if value.is_nan() {
+        String::from("nan")
+    } else if value.is_infinite() {
+        String::from(if value > 0.0 { "inf" } else { "-inf" })
+    } else {
+        let decimals = usize::try_from(decimals).unwrap_or(0);
+        format!("{:.*}", decimals.min(MAX_DECIMALS), value)
+    }
+}
+
+/// The primitives this interpreter provides for the **Double** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "isNaN",
+    "isInfinite",
+    "isFinite",
+    "asStringWithDecimals:",
+    "abs",
+    "negated",
+    "arcSin",
+    "arcCos",
+    "arcTan",
+    "arcTan:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -219,13 +379,23 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "=" => Some(self::eq),
         "<" => Some(self::lt),
         "sqrt" => Some(self::sqrt),
+        "abs" => Some(self::abs),
+        "negated" => Some(self::negated),
         "round" => Some(self::round),
         "cos" => Some(self::cos),
         "sin" => Some(self::sin),
+        "arcSin" => Some(self::arc_sin),
+        "arcCos" => Some(self::arc_cos),
+        "arcTan" => Some(self::arc_tan),
+        "arcTan:" => Some(self::arc_tan2),
         "fromString:" => Some(self::from_string),
         "asString" => Some(self::as_string),
+        "asStringWithDecimals:" => Some(self::as_string_with_decimals),
         "asInteger" => Some(self::as_integer),
         "PositiveInfinity" => Some(self::positive_infinity),
+        "isNaN" => Some(self::is_nan),
+        "isInfinite" => Some(self::is_infinite),
+        "isFinite" => Some(self::is_finite),
         _ => None,
     }
 }
@@ -1,12 +1,15 @@
+use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::rc::Rc;
 
 use num_bigint::{BigInt, Sign};
-use num_traits::ToPrimitive;
+use num_traits::{Signed, ToPrimitive};
 use rand::distributions::Uniform;
 use rand::Rng;
 
 use crate::expect_args;
 use crate::invokable::Return;
+use crate::primitives::string::pad_to_width;
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
 use crate::value::Value;
@@ -44,20 +47,80 @@ fn from_string(universe: &mut Universe, args: Vec<Value>) -> Return {
     }
 }
 
-fn as_string(_: &mut Universe, args: Vec<Value>) -> Return {
+fn as_string(universe: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "Integer>>#asString";
 
     expect_args!(SIGNATURE, args, [
         value => value,
     ]);
 
+    let value = match value {
+        Value::Integer(value) => universe.integer_string(value),
+        Value::BigInteger(value) => Rc::new(value.to_string()),
+        _ => return Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    };
+
+    Return::Local(Value::String(value))
+}
+
+fn print_string_radix(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#printString:";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+        Value::Integer(radix) => radix,
+    ]);
+
+    let value = match value {
+        Value::Integer(value) => BigInt::from(value),
+        Value::BigInteger(value) => value,
+        _ => return Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    };
+
+    match to_radix_string(&value, radix) {
+        Some(string) => Return::Local(Value::String(Rc::new(string))),
+        None => Return::Exception(format!("'{}': invalid radix", SIGNATURE)),
+    }
+}
+
+fn print_string_right_justified(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#printStringRightJustified:";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+        Value::Integer(width) => width,
+    ]);
+
     let value = match value {
         Value::Integer(value) => value.to_string(),
         Value::BigInteger(value) => value.to_string(),
         _ => return Return::Exception(format!("'{}': wrong types", SIGNATURE)),
     };
 
-    Return::Local(Value::String(Rc::new(value)))
+    match pad_to_width(&value, width, ' ', true) {
+        Ok(padded) => Return::Local(Value::String(Rc::new(padded.unwrap_or(value)))),
+        Err(err) => Return::Exception(format!("'{}': {}", SIGNATURE, err)),
+    }
+}
+
+fn print_string_left_justified(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#printStringLeftJustified:";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+        Value::Integer(width) => width,
+    ]);
+
+    let value = match value {
+        Value::Integer(value) => value.to_string(),
+        Value::BigInteger(value) => value.to_string(),
+        _ => return Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    };
+
+    match pad_to_width(&value, width, ' ', false) {
+        Ok(padded) => Return::Local(Value::String(Rc::new(padded.unwrap_or(value)))),
+        Err(err) => Return::Exception(format!("'{}': {}", SIGNATURE, err)),
+    }
 }
 
 fn at_random(_: &mut Universe, args: Vec<Value>) -> Return {
@@ -275,6 +338,54 @@ fn remainder(_: &mut Universe, args: Vec<Value>) -> Return {
     }
 }
 
+fn as_big_integer(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#asBigInteger";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    match value {
+        Value::Integer(value) => Return::Local(Value::BigInteger(BigInt::from(value))),
+        Value::BigInteger(_) => Return::Local(value),
+        _ => Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    }
+}
+
+fn abs(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#abs";
+
+    expect_args!(SIGNATURE, args, [
+        a => a,
+    ]);
+
+    match a {
+        Value::Integer(a) => match a.checked_abs() {
+            Some(value) => Return::Local(Value::Integer(value)),
+            None => Return::Local(Value::BigInteger(BigInt::from(a).abs())),
+        },
+        Value::BigInteger(a) => demote!(a.abs()),
+        _ => Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    }
+}
+
+fn negated(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#negated";
+
+    expect_args!(SIGNATURE, args, [
+        a => a,
+    ]);
+
+    match a {
+        Value::Integer(a) => match a.checked_neg() {
+            Some(value) => Return::Local(Value::Integer(value)),
+            None => Return::Local(Value::BigInteger(-BigInt::from(a))),
+        },
+        Value::BigInteger(a) => demote!(-a),
+        _ => Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    }
+}
+
 fn sqrt(_: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "Integer>>#sqrt";
 
@@ -372,6 +483,9 @@ fn eq(_: &mut Universe, args: Vec<Value>) -> Return {
     match (a, b) {
         (Value::Integer(a), Value::Integer(b)) => Return::Local(Value::Boolean(a == b)),
         (Value::BigInteger(a), Value::BigInteger(b)) => Return::Local(Value::Boolean(a == b)),
+        (Value::Integer(a), Value::BigInteger(b)) | (Value::BigInteger(b), Value::Integer(a)) => {
+            Return::Local(Value::Boolean(BigInt::from(a) == b))
+        }
         (Value::Double(a), Value::Double(b)) => Return::Local(Value::Boolean(a == b)),
         (Value::Integer(a), Value::Double(b)) | (Value::Double(b), Value::Integer(a)) => {
             Return::Local(Value::Boolean((a as f64) == b))
@@ -416,11 +530,72 @@ fn shift_right(_: &mut Universe, args: Vec<Value>) -> Return {
     }
 }
 
+fn to(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Integer>>#to:";
+
+    expect_args!(SIGNATURE, args, [
+        start => start,
+        end => end,
+    ]);
+
+    match materialize_range(&start, &end) {
+        Some(value) => Return::Local(value),
+        None => Return::Exception(format!("'{}': wrong types", SIGNATURE)),
+    }
+}
+
+/// The maximum number of elements that `Integer>>#to:` will materialize.
+const MAX_RANGE_LENGTH: i64 = 1 << 24;
+
+/// Materialize the range from `start` to `end` (inclusive), or `nil` if it is too large.
+fn materialize_range(start: &Value, end: &Value) -> Option<Value> {
+    let (start, end) = match (start, end) {
+        (Value::Integer(start), Value::Integer(end)) => (*start, *end),
+        (Value::Integer(_), Value::BigInteger(_))
+        | (Value::BigInteger(_), Value::Integer(_))
+        | (Value::BigInteger(_), Value::BigInteger(_)) => return Some(Value::Nil),
+        _ => return None,
+    };
+    let values = if end < start {
+        Vec::new()
+    } else if end.saturating_sub(start) >= MAX_RANGE_LENGTH {
+        return Some(Value::Nil);
+    } else {
+        (start..=end).map(Value::Integer).collect()
+    };
+    Some(Value::Array(Rc::new(RefCell::new(values))))
+}
+
+/// Render an integer in the given radix (between 2 and 36), with a leading `-` if it is negative.
+fn to_radix_string(value: &BigInt, radix: i64) -> Option<String> {
+    let radix = u32::try_from(radix)
+        .ok()
+        .filter(|radix| (2..=36).contains(radix))?;
+    Some(value.to_str_radix(radix))
+}
+
+/// The primitives this interpreter provides for the **Integer** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "to:",
+    "printString:",
+    "asString:",
+    "abs",
+    "asBigInteger",
+    "negated",
+    "printStringRightJustified:",
+    "printStringLeftJustified:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
         "fromString:" => Some(self::from_string),
         "asString" => Some(self::as_string),
+        "printString:" => Some(self::print_string_radix),
+        // `asString:` is an alias of `printString:`.
+        "asString:" => Some(self::print_string_radix),
+        "printStringRightJustified:" => Some(self::print_string_right_justified),
+        "printStringLeftJustified:" => Some(self::print_string_left_justified),
         "atRandom" => Some(self::at_random),
         "as32BitSignedValue" => Some(self::as_32bit_signed_value),
         "as32BitUnsignedValue" => Some(self::as_32bit_unsigned_value),
@@ -438,6 +613,10 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         ">>>" => Some(self::shift_right),
         "bitXor:" => Some(self::bitxor),
         "sqrt" => Some(self::sqrt),
+        "abs" => Some(self::abs),
+        "negated" => Some(self::negated),
+        "asBigInteger" => Some(self::as_big_integer),
+        "to:" => Some(self::to),
         _ => None,
     }
 }
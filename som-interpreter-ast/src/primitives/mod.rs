@@ -21,6 +21,8 @@ pub mod system;
 
 pub use self::blocks::{block1, block2, block3};
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use crate::invokable::Return;
 use crate::universe::Universe;
 use crate::value::Value;
@@ -28,6 +30,56 @@ use crate::value::Value;
 /// A interpreter primitive (just a bare function pointer).
 pub type PrimitiveFn = fn(universe: &mut Universe, args: Vec<Value>) -> Return;
 
+static WARN_MISSING_PRIMITIVES: AtomicBool = AtomicBool::new(false);
+
+/// Set whether to print a warning when a class declares a primitive that this interpreter does not implement.
+pub fn set_warn_missing_primitives(enabled: bool) {
+    WARN_MISSING_PRIMITIVES.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether to print a warning when a class declares a primitive that this interpreter does not implement.
+pub fn warn_missing_primitives() -> bool {
+    WARN_MISSING_PRIMITIVES.load(Ordering::Relaxed) && crate::diagnostics_enabled()
+}
+
+static ALLOW_ENVIRONMENT: AtomicBool = AtomicBool::new(false);
+
+/// Set whether SOM programs are allowed to read the process' environment variables.
+pub fn set_allow_environment(enabled: bool) {
+    ALLOW_ENVIRONMENT.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether SOM programs are allowed to read the process' environment variables.
+pub fn allow_environment() -> bool {
+    ALLOW_ENVIRONMENT.load(Ordering::Relaxed)
+}
+
+/// Get the signatures of the primitives this interpreter provides for a given class,
+/// even if that class' definition does not declare them.
+pub fn get_extensions(class_name: impl AsRef<str>) -> &'static [&'static str] {
+    match class_name.as_ref() {
+        "Object" => object::EXTENSIONS,
+        "Array" => array::EXTENSIONS,
+        "Block" => block1::EXTENSIONS,
+        "Class" => class::EXTENSIONS,
+        "Double" => double::EXTENSIONS,
+        "Integer" => integer::EXTENSIONS,
+        "String" => string::EXTENSIONS,
+        "Symbol" => symbol::EXTENSIONS,
+        "System" => system::EXTENSIONS,
+        _ => &[],
+    }
+}
+
+/// Get the signatures of the primitives this interpreter provides for a given class,
+/// replacing that class' own definition of them, if any.
+pub fn get_overrides(class_name: impl AsRef<str>) -> &'static [&'static str] {
+    match class_name.as_ref() {
+        "Object" => object::OVERRIDES,
+        _ => &[],
+    }
+}
+
 /// Macro for checking and destructuring arguments passed to primitives.
 #[macro_export]
 macro_rules! expect_args {
@@ -1,6 +1,7 @@
 use std::collections::hash_map::DefaultHasher;
 use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 use crate::class::Class;
 use crate::invokable::{Invoke, Return};
@@ -39,6 +40,96 @@ fn hashcode(_: &mut Universe, args: Vec<Value>) -> Return {
     Return::Local(Value::Integer(hash))
 }
 
+fn print_nl(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#printNl";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    println!("{}", value.to_string(universe));
+    Return::Local(value)
+}
+
+fn is_nil(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#isNil";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    Return::Local(Value::Boolean(value.is_nil()))
+}
+
+fn assert(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#assert:";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+        Value::Boolean(condition) => condition,
+    ]);
+
+    if condition {
+        Return::Local(value)
+    } else {
+        assertion_failed(universe, value, String::from("assertion failed"))
+    }
+}
+
+fn assert_description(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#assert:description:";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+        Value::Boolean(condition) => condition,
+        Value::String(description) => description,
+    ]);
+
+    if condition {
+        Return::Local(value)
+    } else {
+        assertion_failed(
+            universe,
+            value,
+            format!("assertion failed: {}", description),
+        )
+    }
+}
+
+/// Report a failed assertion through the receiver's `error:`.
+fn assertion_failed(universe: &mut Universe, value: Value, message: String) -> Return {
+    match value.lookup_method(universe, "error:") {
+        Some(error) => error.invoke(universe, vec![value, Value::String(Rc::new(message))]),
+        None => Return::Exception(format!(
+            "{} (and `error:` is not defined on the receiver)",
+            message
+        )),
+    }
+}
+
+fn not_nil(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#notNil";
+
+    expect_args!(SIGNATURE, args, [
+        value => value,
+    ]);
+
+    Return::Local(Value::Boolean(!value.is_nil()))
+}
+
+fn responds_to(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#respondsTo:";
+
+    expect_args!(SIGNATURE, args, [
+        object => object,
+        Value::Symbol(sym) => sym,
+    ]);
+
+    let signature = universe.lookup_symbol(sym);
+    let responds = object.lookup_method(universe, signature).is_some();
+    Return::Local(Value::Boolean(responds))
+}
+
 fn eq(_: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &'static str = "Object>>#==";
 
@@ -47,7 +138,13 @@ fn eq(_: &mut Universe, args: Vec<Value>) -> Return {
         b => b,
     ]);
 
-    Return::Local(Value::Boolean(a == b))
+    // Arrays compare their contents with `Value::eq`, so identity has to be checked separately.
+    let identical = match (&a, &b) {
+        (Value::Array(a), Value::Array(b)) => Rc::ptr_eq(a, b),
+        _ => a == b,
+    };
+
+    Return::Local(Value::Boolean(identical))
 }
 
 fn perform(universe: &mut Universe, args: Vec<Value>) -> Return {
@@ -245,6 +342,34 @@ fn gather_locals(universe: &mut Universe, class: SOMRef<Class>) -> Vec<String> {
     fields
 }
 
+fn as_string(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Object>>#asString";
+
+    expect_args!(SIGNATURE, args, [
+        object => object,
+    ]);
+
+    let class = object.class(universe);
+    let description = describe_instance(class.borrow().name());
+
+    Return::Local(Value::String(Rc::new(description)))
+}
+
+/// Describes an instance of the named class, as in "a Point" or "an Object".
+fn describe_instance(class_name: &str) -> String {
+    let article = match class_name.chars().next() {
+        Some(ch) if "AEIOUaeiou".contains(ch) => "an",
+        _ => "a",
+    };
+    format!("{} {}", article, class_name)
+}
+
+/// The primitives this interpreter provides for the **Object** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &["printNl", "assert:", "assert:description:"];
+
+/// The primitives this interpreter provides for the **Object** class, in place of the core library's definitions.
+pub static OVERRIDES: &[&str] = &["asString", "respondsTo:", "isNil", "notNil"];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -257,6 +382,13 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "perform:withArguments:inSuperclass:" => Some(self::perform_with_arguments_in_super_class),
         "instVarAt:" => Some(self::inst_var_at),
         "instVarAt:put:" => Some(self::inst_var_at_put),
+        "printNl" => Some(self::print_nl),
+        "respondsTo:" => Some(self::responds_to),
+        "isNil" => Some(self::is_nil),
+        "notNil" => Some(self::not_nil),
+        "assert:" => Some(self::assert),
+        "assert:description:" => Some(self::assert_description),
+        "asString" => Some(self::as_string),
         "==" => Some(self::eq),
         _ => None,
     }
@@ -18,10 +18,27 @@ fn as_string(universe: &mut Universe, args: Vec<Value>) -> Return {
     )))
 }
 
+fn eq(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "Symbol>>#=";
+
+    expect_args!(SIGNATURE, args, [
+        Value::Symbol(sym) => sym,
+        other => other,
+    ]);
+
+    // Symbols are interned, so equal symbols share the same ID, and a symbol never equals a string.
+    let eq = matches!(other, Value::Symbol(other) if other == sym);
+    Return::Local(Value::Boolean(eq))
+}
+
+/// The primitives this interpreter provides for the **Symbol** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &["="];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
         "asString" => Some(self::as_string),
+        "=" => Some(self::eq),
         _ => None,
     }
 }
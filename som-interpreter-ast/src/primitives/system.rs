@@ -1,6 +1,7 @@
 use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
 // use std::io::BufRead;
-// use std::rc::Rc;
+use std::rc::Rc;
 
 use crate::expect_args;
 use crate::invokable::Return;
@@ -62,6 +63,28 @@ fn load(universe: &mut Universe, args: Vec<Value>) -> Return {
     }
 }
 
+fn load_module(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "System>>#loadModule:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::System,
+        name => name,
+    ]);
+
+    let name = match name {
+        Value::String(ref name) => name.to_string(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym).to_string(),
+        _ => return Return::Exception(format!("'{}': wrong type", SIGNATURE)),
+    };
+
+    // Classes that are already loaded are not loaded again, so that existing references to them stay valid.
+    let class = match universe.lookup_global(name.as_str()) {
+        Some(Value::Class(class)) => Some(class),
+        _ => universe.load_class(name).ok(),
+    };
+    Return::Local(class.map_or(Value::Nil, Value::Class))
+}
+
 fn global(universe: &mut Universe, args: Vec<Value>) -> Return {
     const SIGNATURE: &str = "System>>#global:";
 
@@ -133,6 +156,61 @@ fn full_gc(_: &mut Universe, args: Vec<Value>) -> Return {
     Return::Local(Value::Boolean(false))
 }
 
+fn current_time_millis(_: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "System>>#currentTimeMillis";
+
+    expect_args!(SIGNATURE, args, [Value::System]);
+
+    Return::Local(unix_time_millis())
+}
+
+/// Get the current Unix time in milliseconds (negative if the clock is set before the epoch).
+fn unix_time_millis() -> Value {
+    let millis = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_millis() as i128,
+        Err(err) => -(err.duration().as_millis() as i128),
+    };
+    i64::try_from(millis)
+        .map(Value::Integer)
+        .unwrap_or_else(|_| Value::BigInteger(millis.into()))
+}
+
+fn environment_variable(universe: &mut Universe, args: Vec<Value>) -> Return {
+    const SIGNATURE: &str = "System>>#environmentVariable:";
+
+    expect_args!(SIGNATURE, args, [
+        Value::System,
+        name => name,
+    ]);
+
+    if !crate::primitives::allow_environment() {
+        return Return::Exception(format!(
+            "'{}': access to the environment is disabled",
+            SIGNATURE
+        ));
+    }
+
+    let name = match name {
+        Value::String(ref string) => string,
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => return Return::Exception(format!("'{}': wrong type", SIGNATURE)),
+    };
+
+    let value = std::env::var(name)
+        .map(|value| Value::String(Rc::new(value)))
+        .unwrap_or(Value::Nil);
+    Return::Local(value)
+}
+
+/// The primitives this interpreter provides for the **System** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "currentTimeMillis",
+    "environmentVariable:",
+    "at:",
+    "at:put:",
+    "loadModule:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -140,12 +218,18 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "printString:" => Some(self::print_string),
         "printNewline" => Some(self::print_newline),
         "load:" => Some(self::load),
+        "loadModule:" => Some(self::load_module),
         "ticks" => Some(self::ticks),
         "time" => Some(self::time),
+        "currentTimeMillis" => Some(self::current_time_millis),
+        "environmentVariable:" => Some(self::environment_variable),
         "fullGC" => Some(self::full_gc),
         "exit:" => Some(self::exit),
         "global:" => Some(self::global),
         "global:put:" => Some(self::global_put),
+        // `Smalltalk at:` and `Smalltalk at:put:`, the dictionary-like view of the globals.
+        "at:" => Some(self::global),
+        "at:put:" => Some(self::global_put),
         _ => None,
     }
 }
@@ -5,7 +5,7 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{anyhow, Error};
 
@@ -17,6 +17,39 @@ use crate::invokable::{Invoke, Return};
 use crate::value::Value;
 use crate::SOMRef;
 
+/// The selectors of the conditionals only understood by booleans.
+const BOOLEAN_CONDITIONALS: &[&str] =
+    &["ifTrue:", "ifFalse:", "ifTrue:ifFalse:", "ifFalse:ifTrue:"];
+
+/// The range of integers whose string representations are cached by [`Universe::integer_string`].
+const SMALL_INTEGERS: std::ops::RangeInclusive<i64> = -128..=256;
+
+/// The error reported when a global cannot be resolved, with strict globals.
+fn unknown_global_message(name: &str) -> String {
+    format!(
+        "the global '{}' is not defined (strict globals are enabled)",
+        name
+    )
+}
+
+/// The error reported when a boolean conditional is sent to a value which is not a boolean.
+fn non_boolean_receiver_message(signature: &str, class: &SOMRef<Class>) -> String {
+    format!(
+        "'{}' was sent to an instance of {}, but a Boolean was expected",
+        signature,
+        class.borrow().name(),
+    )
+}
+
+/// The error reported when a message is not understood by a value which has no `doesNotUnderstand:arguments:`.
+fn missing_handler_message(signature: &str, class: &SOMRef<Class>) -> String {
+    format!(
+        "could not find method '{}>>#{}', and its receiver does not define 'doesNotUnderstand:arguments:'",
+        class.borrow().name(),
+        signature,
+    )
+}
+
 /// The core classes of the SOM interpreter.
 ///
 /// This struct allows to always keep a reference to important classes,
@@ -77,12 +110,18 @@ pub struct Universe {
     pub globals: HashMap<String, Value>,
     /// The path to search in for new classes.
     pub classpath: Vec<PathBuf>,
+    /// The cached string representations of small integers.
+    small_integer_strings: Vec<Option<Rc<String>>>,
     /// The interpreter's core classes.
     pub core: CoreClasses,
+    /// Whether an unresolved global is an error, instead of being handed to `unknownGlobal:`.
+    pub strict_globals: bool,
     /// The time record of the universe's creation.
     pub start_time: Instant,
     /// The interpreter's stack frames.
     pub frames: Vec<SOMRef<Frame>>,
+    /// The time budgets (and deadlines) of the blocks currently running through `Block>>#valueWithTimeout:`.
+    timeouts: Vec<(Duration, Instant)>,
 }
 
 impl Universe {
@@ -182,12 +221,16 @@ impl Universe {
         globals.insert("false".into(), Value::Boolean(false));
         globals.insert("nil".into(), Value::Nil);
         globals.insert("system".into(), Value::System);
+        globals.insert("Smalltalk".into(), Value::System);
 
         Ok(Self {
             globals,
             interner,
             classpath,
+            small_integer_strings: vec![None; SMALL_INTEGERS.count()],
+            strict_globals: false,
             frames: Vec::new(),
+            timeouts: Vec::new(),
             start_time: Instant::now(),
             core: CoreClasses {
                 object_class,
@@ -213,26 +256,46 @@ impl Universe {
         })
     }
 
+    /// Enumerate the places where a class' source code may be found, in lookup order.
+    ///
+    /// The embedded core library (if enabled) comes first, with its source code given directly,
+    /// followed by the corresponding file within each of the given classpath entries.
+    fn class_sources(
+        class_name: &str,
+        classpath: impl Iterator<Item = PathBuf>,
+    ) -> impl Iterator<Item = (PathBuf, Option<&'static str>)> {
+        let file_name = format!("{}.som", class_name);
+        let embedded = som_core::corelib::lookup(class_name).map(|contents| {
+            (
+                PathBuf::from("<embedded>").join(file_name.as_str()),
+                Some(contents),
+            )
+        });
+        let on_disk = classpath.map(move |path| (path.join(file_name.as_str()), None));
+        embedded.into_iter().chain(on_disk)
+    }
+
     /// Load a system class (with an incomplete hierarchy).
     pub fn load_system_class(
         classpath: &[impl AsRef<Path>],
         class_name: impl Into<String>,
     ) -> Result<SOMRef<Class>, Error> {
         let class_name = class_name.into();
-        for path in classpath {
-            let mut path = path.as_ref().join(class_name.as_str());
-            path.set_extension("som");
-
+        let paths = classpath.iter().map(|path| path.as_ref().to_path_buf());
+        for (path, embedded) in Self::class_sources(class_name.as_str(), paths) {
             // Read file contents.
-            let contents = match fs::read_to_string(path.as_path()) {
-                Ok(contents) => contents,
-                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
-                Err(err) => return Err(Error::from(err)),
+            let contents = match embedded {
+                Some(contents) => contents.to_string(),
+                None => match fs::read_to_string(path.as_path()) {
+                    Ok(contents) => contents,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(Error::from(err)),
+                },
             };
 
             // Collect all tokens from the file.
             let tokens: Vec<_> = som_lexer::Lexer::new(contents.as_str())
-                .skip_comments(true)
+                .skip_comments(false)
                 .skip_whitespace(true)
                 .collect();
 
@@ -258,19 +321,20 @@ impl Universe {
     /// Load a class from its name into this universe.
     pub fn load_class(&mut self, class_name: impl Into<String>) -> Result<SOMRef<Class>, Error> {
         let class_name = class_name.into();
-        for path in self.classpath.iter() {
-            let mut path = path.join(class_name.as_str());
-            path.set_extension("som");
-
+        let paths = self.classpath.clone().into_iter();
+        for (path, embedded) in Self::class_sources(class_name.as_str(), paths) {
             // Read file contents.
-            let contents = match fs::read_to_string(path.as_path()) {
-                Ok(contents) => contents,
-                Err(_) => continue,
+            let contents = match embedded {
+                Some(contents) => contents.to_string(),
+                None => match fs::read_to_string(path.as_path()) {
+                    Ok(contents) => contents,
+                    Err(_) => continue,
+                },
             };
 
             // Collect all tokens from the file.
             let tokens: Vec<_> = som_lexer::Lexer::new(contents.as_str())
-                .skip_comments(true)
+                .skip_comments(false)
                 .skip_whitespace(true)
                 .collect();
 
@@ -361,7 +425,7 @@ impl Universe {
 
         // Collect all tokens from the file.
         let tokens: Vec<_> = som_lexer::Lexer::new(contents.as_str())
-            .skip_comments(true)
+            .skip_comments(false)
             .skip_whitespace(true)
             .collect();
 
@@ -393,6 +457,81 @@ impl Universe {
         Ok(class)
     }
 
+    /// Load a class from its source code into this universe, without going through the classpath.
+    ///
+    /// The class' superclass must already be loaded.
+    pub fn load_class_from_source(&mut self, source: &str) -> Result<SOMRef<Class>, Error> {
+        // Collect all tokens from the source.
+        let tokens: Vec<_> = som_lexer::Lexer::new(source)
+            .skip_comments(false)
+            .skip_whitespace(true)
+            .collect();
+
+        // Parse class definition from the tokens.
+        let defn = match som_parser::parse_file(tokens.as_slice()) {
+            Some(defn) => defn,
+            None => return Err(Error::msg("could not parse class source")),
+        };
+
+        let super_class = if let Some(ref super_class) = defn.super_class {
+            match self.lookup_global(super_class) {
+                Some(Value::Class(class)) => class,
+                _ => {
+                    return Err(anyhow!(
+                        "could not find the '{}' class (superclass of '{}')",
+                        super_class,
+                        defn.name,
+                    ))
+                }
+            }
+        } else {
+            self.core.object_class.clone()
+        };
+
+        let class = Class::from_class_def(defn).map_err(Error::msg)?;
+        set_super_class(&class, &super_class, &self.core.metaclass_class);
+
+        self.globals.insert(
+            class.borrow().name().to_string(),
+            Value::Class(class.clone()),
+        );
+
+        Ok(class)
+    }
+
+    /// Add methods to an existing class, from the source code of a class extension.
+    ///
+    /// The extension is written like the body of a class definition (its instance methods, optionally
+    /// followed by `----` and its class methods), but cannot declare any fields.
+    /// Methods with the same signatures as existing ones replace them.
+    pub fn install_class_extension(&mut self, class_name: &str, source: &str) -> Result<(), Error> {
+        let class = match self.lookup_global(class_name) {
+            Some(Value::Class(class)) => class,
+            _ => self.load_class(class_name)?,
+        };
+
+        // Parse the extension as a definition of the extended class.
+        let source = format!("{} = ( {} )", class_name, source);
+        let tokens: Vec<_> = som_lexer::Lexer::new(source.as_str())
+            .skip_comments(false)
+            .skip_whitespace(true)
+            .collect();
+        let defn = match som_parser::parse_file(tokens.as_slice()) {
+            Some(defn) => defn,
+            None => return Err(Error::msg("could not parse class extension")),
+        };
+
+        if !defn.instance_locals.is_empty() || !defn.static_locals.is_empty() {
+            return Err(anyhow!(
+                "the extension of the '{}' class cannot declare fields",
+                class_name
+            ));
+        }
+
+        Class::install_methods(&class, &defn);
+        Ok(())
+    }
+
     /// Get the **Nil** class.
     pub fn nil_class(&self) -> SOMRef<Class> {
         self.core.nil_class.clone()
@@ -475,6 +614,26 @@ impl Universe {
         ret
     }
 
+    /// Execute a piece of code within the given time budget (as checked by `Universe::expired_timeout`).
+    pub fn with_timeout<T>(&mut self, budget: Duration, func: impl FnOnce(&mut Self) -> T) -> T {
+        self.timeouts.push((budget, Instant::now() + budget));
+        let ret = func(self);
+        self.timeouts.pop();
+        ret
+    }
+
+    /// Get the time budget of the outermost running block whose budget has run out, if any.
+    pub fn expired_timeout(&self) -> Option<Duration> {
+        if self.timeouts.is_empty() {
+            return None;
+        }
+        let now = Instant::now();
+        self.timeouts
+            .iter()
+            .find(|(_, deadline)| now >= *deadline)
+            .map(|(budget, _)| *budget)
+    }
+
     /// Get the current frame.
     pub fn current_frame(&self) -> &SOMRef<Frame> {
         self.frames.last().expect("no frames left")
@@ -490,11 +649,29 @@ impl Universe {
         self.interner.intern(symbol)
     }
 
+    /// Intern a batch of symbols, returning them in the same order.
+    pub fn intern_symbols(&mut self, symbols: &[&str]) -> Vec<Interned> {
+        self.interner.intern_all(symbols)
+    }
+
     /// Lookup a symbol.
     pub fn lookup_symbol(&self, symbol: Interned) -> &str {
         self.interner.lookup(symbol)
     }
 
+    /// Get the string representation of an integer.
+    ///
+    /// Small integers share a single, lazily-built, string per value.
+    pub fn integer_string(&mut self, value: i64) -> Rc<String> {
+        if !SMALL_INTEGERS.contains(&value) {
+            return Rc::new(value.to_string());
+        }
+        let index = (value - SMALL_INTEGERS.start()) as usize;
+        self.small_integer_strings[index]
+            .get_or_insert_with(|| Rc::new(value.to_string()))
+            .clone()
+    }
+
     /// Search for a local binding.
     pub fn lookup_local(&self, name: impl AsRef<str>) -> Option<Value> {
         let name = name.as_ref();
@@ -535,14 +712,29 @@ impl Universe {
         Some(initialize.invoke(self, vec![value, Value::Block(block)]))
     }
 
-    /// Call `doesNotUnderstand:` on the given value, if it is defined.
+    /// Call `doesNotUnderstand:` on the given value, or `error:` if the former is not defined.
     pub fn does_not_understand(
         &mut self,
         value: Value,
         symbol: impl AsRef<str>,
         args: Vec<Value>,
     ) -> Option<Return> {
-        let initialize = value.lookup_method(self, "doesNotUnderstand:arguments:")?;
+        let initialize = match value.lookup_method(self, "doesNotUnderstand:arguments:") {
+            Some(initialize) => initialize,
+            None => {
+                let message = missing_handler_message(symbol.as_ref(), &value.class(self));
+                return self.error(value, message);
+            }
+        };
+
+        let holder = initialize.holder().upgrade().unwrap();
+        if BOOLEAN_CONDITIONALS.contains(&symbol.as_ref())
+            && Rc::ptr_eq(&holder, &self.core.object_class)
+        {
+            let message = non_boolean_receiver_message(symbol.as_ref(), &value.class(self));
+            return self.error(value, message);
+        }
+
         let sym = self.intern_symbol(symbol.as_ref());
         let sym = Value::Symbol(sym);
         let args = Value::Array(Rc::new(RefCell::new(args)));
@@ -550,8 +742,24 @@ impl Universe {
         Some(initialize.invoke(self, vec![value, sym, args]))
     }
 
+    /// Call `error:` on the given value, if it is defined.
+    pub fn error(&mut self, value: Value, message: String) -> Option<Return> {
+        let error = value.lookup_method(self, "error:")?;
+
+        Some(error.invoke(self, vec![value, Value::String(Rc::new(message))]))
+    }
+
     /// Call `unknownGlobal:` on the given value, if it is defined.
+    ///
+    /// With strict globals, the global is only looked up as a class in the classpath, and reported as an error if not found.
     pub fn unknown_global(&mut self, value: Value, name: impl AsRef<str>) -> Option<Return> {
+        if self.strict_globals {
+            return match self.load_class(name.as_ref()) {
+                Ok(class) => Some(Return::Local(Value::Class(class))),
+                Err(_) => Some(Return::Exception(unknown_global_message(name.as_ref()))),
+            };
+        }
+
         let sym = self.intern_symbol(name.as_ref());
         let method = value.lookup_method(self, "unknownGlobal:")?;
 
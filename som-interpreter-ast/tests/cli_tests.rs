@@ -0,0 +1,21 @@
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn print_nl_prints_receiver() {
+    let directory = std::env::temp_dir().join("som-interpreter-ast-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("PrintNl.som");
+    fs::write(&file, "PrintNl = ( run = ( (3 printNl + 1) println ) )")
+        .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-ast"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n4\n");
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "embedded-corelib")]
+
+use std::fs;
+
+use som_interpreter_ast::invokable::{Invoke, Return};
+use som_interpreter_ast::universe::Universe;
+use som_interpreter_ast::value::Value;
+
+#[test]
+fn runs_without_corelib_on_disk() {
+    let dir = std::env::temp_dir().join("som-interpreter-ast-embedded-corelib-tests");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Main.som");
+    fs::write(
+        &path,
+        "Main = ( ---- run = ( ^ (1 to: 6) inject: 0 into: [ :a :b | a + b ] ) )",
+    )
+    .unwrap();
+
+    let mut universe = Universe::with_classpath(vec![]).expect("could not setup test universe");
+    let class = universe
+        .load_class_from_path(&path)
+        .expect("could not load test class");
+
+    let receiver = Value::Class(class);
+    let method = receiver
+        .lookup_method(&universe, "run")
+        .expect("could not find 'Main class>>#run'");
+
+    match method.invoke(&mut universe, vec![receiver]) {
+        Return::Local(value) => assert_eq!(value, Value::Integer(21)),
+        ret => panic!("unexpected result: {:?}", ret),
+    }
+}
@@ -0,0 +1,1692 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+use som_interpreter_ast::class::Class;
+use som_interpreter_ast::evaluate::Evaluate;
+use som_interpreter_ast::frame::FrameKind;
+use som_interpreter_ast::instance::Instance;
+use som_interpreter_ast::invokable::{Invoke, Return};
+use som_interpreter_ast::universe::Universe;
+use som_interpreter_ast::value::Value;
+use som_interpreter_ast::SOMRef;
+use som_lexer::{Lexer, Token};
+
+use som_parser::lang;
+
+fn setup_universe() -> Universe {
+    let classpath = vec![PathBuf::from("../core-lib/Smalltalk")];
+    Universe::with_classpath(classpath).expect("could not setup test universe")
+}
+
+fn load_class(universe: &mut Universe, source: &str, super_class: &SOMRef<Class>) -> SOMRef<Class> {
+    let mut lexer = Lexer::new(source)
+        .skip_comments(false)
+        .skip_whitespace(true);
+    let tokens: Vec<Token> = lexer.by_ref().collect();
+    assert!(
+        lexer.text().is_empty(),
+        "could not fully tokenize test class"
+    );
+
+    let class_def = som_parser::parse_file(tokens.as_slice()).unwrap();
+    let name = class_def.name.clone();
+    let class = Class::from_class_def(class_def).expect("could not create test class");
+
+    class.borrow_mut().set_super_class(super_class);
+    class
+        .borrow()
+        .class()
+        .borrow_mut()
+        .set_super_class(&super_class.borrow().class());
+    class
+        .borrow()
+        .class()
+        .borrow_mut()
+        .set_class(&universe.metaclass_class());
+
+    universe.assign_global(name, Value::Class(class.clone()));
+
+    class
+}
+
+fn evaluate(universe: &mut Universe, expr: &str) -> Return {
+    let mut lexer = Lexer::new(expr).skip_comments(true).skip_whitespace(true);
+    let tokens: Vec<Token> = lexer.by_ref().collect();
+    assert!(
+        lexer.text().is_empty(),
+        "could not fully tokenize test expression"
+    );
+
+    let ast = som_parser::apply(lang::expression(), tokens.as_slice()).unwrap();
+
+    let kind = FrameKind::Method {
+        holder: universe.system_class(),
+        self_value: Value::System,
+    };
+    universe.with_frame(kind, |universe| ast.evaluate(universe))
+}
+
+fn evaluate_method(universe: &mut Universe, body: &str) -> Return {
+    let object_class = universe.core.object_class.clone();
+    let class = load_class(
+        universe,
+        &format!("PrimitiveTest = ( run = ( {} ) )", body),
+        &object_class,
+    );
+
+    let method = class
+        .borrow()
+        .lookup_method("run")
+        .expect("method not found ??");
+    method.invoke(universe, vec![Value::Class(class)])
+}
+
+fn expect_value(universe: &mut Universe, expr: &str, expected: Value) {
+    match evaluate(universe, expr) {
+        Return::Local(output) => assert_eq!(output, expected, "unexpected output for '{}'", expr),
+        Return::NonLocal(_, _) => panic!("unexpected non-local return from '{}'", expr),
+        Return::Restart => panic!("unexpected `restart` from '{}'", expr),
+        Return::Exception(err) => panic!("unexpected exception from '{}': '{}'", expr, err),
+    }
+}
+
+#[test]
+fn print_nl_returns_receiver() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "3 printNl", Value::Integer(3));
+    expect_value(&mut universe, "3 printNl + 1", Value::Integer(4));
+}
+
+#[test]
+fn array_collect() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "(#(1 2 3) collect: [ :e | e * 2 ]) length",
+        Value::Integer(3),
+    );
+    expect_value(
+        &mut universe,
+        "(#(1 2 3) collect: [ :e | e * 2 ]) at: 3",
+        Value::Integer(6),
+    );
+
+    let early = universe.intern_symbol("early");
+    let output = evaluate_method(
+        &mut universe,
+        "#(1 2 3) collect: [ :e | e = 2 ifTrue: [ ^ #early ]. e ]. ^ #late",
+    );
+    match output {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(early)),
+        Return::NonLocal(_, _) => panic!("non-local return escaped its method"),
+        Return::Restart => panic!("unexpected `restart`"),
+        Return::Exception(err) => panic!("unexpected exception: '{}'", err),
+    }
+}
+
+#[test]
+fn string_as_number() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "'42' asNumber", Value::Integer(42));
+    expect_value(&mut universe, "' -7 ' asNumber", Value::Integer(-7));
+    expect_value(&mut universe, "'2.5' asNumber", Value::Double(2.5));
+    expect_value(&mut universe, "'1e3' asNumber", Value::Double(1000.0));
+    expect_value(
+        &mut universe,
+        "'123456789012345678901234567890' asNumber",
+        Value::BigInteger("123456789012345678901234567890".parse().unwrap()),
+    );
+    expect_value(&mut universe, "'oops' asNumber", Value::Nil);
+    expect_value(&mut universe, "'' asNumber", Value::Nil);
+}
+
+#[test]
+fn integer_as_string() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "-123 asString",
+        Value::String(Rc::new(String::from("-123"))),
+    );
+    expect_value(
+        &mut universe,
+        "-123 asString = '-123'",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "123456789012345678901234567890 asString",
+        Value::String(Rc::new(String::from("123456789012345678901234567890"))),
+    );
+    expect_value(
+        &mut universe,
+        "-123456789012345678901234567890 asString",
+        Value::String(Rc::new(String::from("-123456789012345678901234567890"))),
+    );
+}
+
+#[test]
+fn class_all_inst_var_names() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.core.object_class.clone();
+    let parent = load_class(&mut universe, "InstVarParent = ( | a b | )", &object_class);
+    load_class(
+        &mut universe,
+        "InstVarChild = InstVarParent ( | c | )",
+        &parent,
+    );
+
+    let names: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|name| Value::Symbol(universe.intern_symbol(name)))
+        .collect();
+    expect_value(
+        &mut universe,
+        "InstVarChild allInstVarNames",
+        Value::Array(Rc::new(RefCell::new(names))),
+    );
+    expect_value(
+        &mut universe,
+        "InstVarChild allInstVarNames length",
+        Value::Integer(3),
+    );
+}
+
+#[test]
+fn class_comment() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.core.object_class.clone();
+    load_class(
+        &mut universe,
+        "Commented = ( \"A documented class.\" foo = ( \"not the class comment\" ^ 1 ) )",
+        &object_class,
+    );
+    load_class(&mut universe, "Uncommented = ( )", &object_class);
+
+    expect_value(
+        &mut universe,
+        "Commented comment",
+        Value::String(Rc::new(String::from("A documented class."))),
+    );
+    expect_value(
+        &mut universe,
+        "Uncommented comment",
+        Value::String(Rc::new(String::new())),
+    );
+}
+
+#[test]
+fn object_responds_to() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "3 respondsTo: #+", Value::Boolean(true));
+    expect_value(
+        &mut universe,
+        "3 respondsTo: #printNl",
+        Value::Boolean(true),
+    );
+    expect_value(&mut universe, "3 respondsTo: #nope", Value::Boolean(false));
+    expect_value(
+        &mut universe,
+        "'abc' respondsTo: #length",
+        Value::Boolean(true),
+    );
+
+    // The core library's definition would ask `self class`, which this class lies about.
+    let object_class = universe.core.object_class.clone();
+    load_class(
+        &mut universe,
+        "Impostor = ( class = ( ^Object ) frob = ( ^1 ) )",
+        &object_class,
+    );
+    expect_value(
+        &mut universe,
+        "Impostor new respondsTo: #frob",
+        Value::Boolean(true),
+    );
+}
+
+#[test]
+fn double_special_values() {
+    let mut universe = setup_universe();
+
+    let cases = [
+        ("1.5", false, false, true),
+        ("Double PositiveInfinity", false, true, false),
+        ("(0.0 - Double PositiveInfinity)", false, true, false),
+        (
+            "(Double PositiveInfinity - Double PositiveInfinity)",
+            true,
+            false,
+            false,
+        ),
+    ];
+    for (expr, is_nan, is_infinite, is_finite) in cases.iter() {
+        expect_value(
+            &mut universe,
+            &format!("{} isNaN", expr),
+            Value::Boolean(*is_nan),
+        );
+        expect_value(
+            &mut universe,
+            &format!("{} isInfinite", expr),
+            Value::Boolean(*is_infinite),
+        );
+        expect_value(
+            &mut universe,
+            &format!("{} isFinite", expr),
+            Value::Boolean(*is_finite),
+        );
+    }
+}
+
+#[test]
+fn system_current_time_millis() {
+    let mut universe = setup_universe();
+
+    // 2020-01-01T00:00:00Z, as a lower bound.
+    expect_value(
+        &mut universe,
+        "system currentTimeMillis > 1577836800000",
+        Value::Boolean(true),
+    );
+    match evaluate(&mut universe, "system currentTimeMillis") {
+        Return::Local(Value::Integer(millis)) => assert!(millis > 1_577_836_800_000),
+        Return::Local(value) => panic!("unexpected value: {:?}", value),
+        _ => panic!("unexpected non-local result"),
+    }
+}
+
+#[test]
+fn array_group_by() {
+    let mut universe = setup_universe();
+
+    fn array(values: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(values)))
+    }
+
+    expect_value(
+        &mut universe,
+        "#(1 2 3 4) groupBy: [ :e | e % 2 ]",
+        array(vec![
+            array(vec![
+                Value::Integer(1),
+                array(vec![Value::Integer(1), Value::Integer(3)]),
+            ]),
+            array(vec![
+                Value::Integer(0),
+                array(vec![Value::Integer(2), Value::Integer(4)]),
+            ]),
+        ]),
+    );
+    expect_value(
+        &mut universe,
+        "(#() groupBy: [ :e | e ]) length",
+        Value::Integer(0),
+    );
+
+    let early = universe.intern_symbol("early");
+    match evaluate_method(
+        &mut universe,
+        "#(1 2 3) groupBy: [ :e | e = 2 ifTrue: [ ^ #early ]. e ]. ^ #late",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(early)),
+        Return::NonLocal(_, _) => panic!("non-local return escaped its method"),
+        Return::Restart => panic!("unexpected `restart`"),
+        Return::Exception(err) => panic!("unexpected exception: '{}'", err),
+    }
+}
+
+#[test]
+fn object_default_equality_is_identity() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.core.object_class.clone();
+    load_class(&mut universe, "EqualityTest = ( | x | )", &object_class);
+
+    expect_value(
+        &mut universe,
+        "EqualityTest new = EqualityTest new",
+        Value::Boolean(false),
+    );
+    match evaluate_method(&mut universe, "| a | a := EqualityTest new. ^ a = a") {
+        Return::Local(output) => assert_eq!(output, Value::Boolean(true)),
+        _ => panic!("unexpected non-local result"),
+    }
+    expect_value(
+        &mut universe,
+        "(Array new: 1) == (Array new: 1)",
+        Value::Boolean(false),
+    );
+    match evaluate_method(&mut universe, "| a | a := Array new: 1. ^ a == a") {
+        Return::Local(output) => assert_eq!(output, Value::Boolean(true)),
+        _ => panic!("unexpected non-local result"),
+    }
+    expect_value(&mut universe, "3 = 3", Value::Boolean(true));
+    expect_value(&mut universe, "'abc' = 'abc'", Value::Boolean(true));
+}
+
+#[test]
+fn integer_to() {
+    let mut universe = setup_universe();
+
+    let range = (1..=5).map(Value::Integer).collect();
+    expect_value(
+        &mut universe,
+        "1 to: 5",
+        Value::Array(Rc::new(RefCell::new(range))),
+    );
+    expect_value(&mut universe, "(-2 to: -2) length", Value::Integer(1));
+    expect_value(&mut universe, "(5 to: 1) length", Value::Integer(0));
+    expect_value(&mut universe, "0 to: 100000000", Value::Nil);
+    expect_value(
+        &mut universe,
+        "0 to: 123456789012345678901234567890",
+        Value::Nil,
+    );
+}
+
+#[test]
+fn array_do_with_index() {
+    let mut universe = setup_universe();
+
+    match evaluate_method(
+        &mut universe,
+        "| sum | sum := 0. #(10 20 30) doWithIndex: [ :e :i | sum := sum + (i * e) ]. ^ sum",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Integer(140)),
+        _ => panic!("unexpected non-local result"),
+    }
+
+    let early = universe.intern_symbol("early");
+    for selector in ["do:", "doWithIndex:"].iter() {
+        let block = if *selector == "do:" {
+            "[ :e | e = 2 ifTrue: [ ^ #early ] ]"
+        } else {
+            "[ :e :i | i = 2 ifTrue: [ ^ #early ] ]"
+        };
+        match evaluate_method(
+            &mut universe,
+            &format!("#(1 2 3) {} {}. ^ #late", selector, block),
+        ) {
+            Return::Local(output) => assert_eq!(output, Value::Symbol(early), "for '{}'", selector),
+            Return::NonLocal(_, _) => panic!("non-local return escaped its method"),
+            Return::Restart => panic!("unexpected `restart`"),
+            Return::Exception(err) => panic!("unexpected exception: '{}'", err),
+        }
+    }
+}
+
+#[test]
+fn array_new_is_filled_with_nil() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "Array new: 1000",
+        Value::Array(Rc::new(RefCell::new(vec![Value::Nil; 1000]))),
+    );
+}
+
+#[test]
+fn string_reverse() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "'abc' reverse",
+        Value::String(Rc::new(String::from("cba"))),
+    );
+    expect_value(
+        &mut universe,
+        "'' reverse",
+        Value::String(Rc::new(String::new())),
+    );
+    expect_value(
+        &mut universe,
+        "'héllo wörld ✓' reverse",
+        Value::String(Rc::new(String::from("✓ dlröw olléh"))),
+    );
+}
+
+#[test]
+fn array_integral_double_index() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "#(10 20 30) at: 2.0", Value::Integer(20));
+    match evaluate_method(
+        &mut universe,
+        "| a | a := Array new: 3. a at: 3.0 put: 42. ^ a at: 3",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Integer(42)),
+        _ => panic!("unexpected non-local result"),
+    }
+    expect_value(
+        &mut universe,
+        "'hello' primSubstringFrom: 2.0 to: 4.0",
+        Value::String(Rc::new(String::from("ell"))),
+    );
+
+    match evaluate(&mut universe, "#(10 20 30) at: 2.5") {
+        Return::Exception(err) => assert!(err.contains("invalid index"), "{}", err),
+        _ => panic!("expected an exception for a non-integral index"),
+    }
+}
+
+#[test]
+fn boolean_short_circuit() {
+    let mut universe = setup_universe();
+
+    match evaluate_method(
+        &mut universe,
+        "| evaluated | evaluated := false. false and: [ evaluated := true ]. true or: [ evaluated := true ]. ^ evaluated",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Boolean(false)),
+        _ => panic!("unexpected non-local result"),
+    }
+    expect_value(&mut universe, "true and: [ 42 ]", Value::Integer(42));
+    expect_value(&mut universe, "false or: [ 42 ]", Value::Integer(42));
+    expect_value(&mut universe, "true & false", Value::Boolean(false));
+    expect_value(&mut universe, "false | true", Value::Boolean(true));
+}
+
+#[test]
+fn array_first_last() {
+    let mut universe = setup_universe();
+
+    let array = |values: &[i64]| {
+        Value::Array(Rc::new(RefCell::new(
+            values.iter().copied().map(Value::Integer).collect(),
+        )))
+    };
+
+    expect_value(&mut universe, "#(1 2 3 4) first: 2", array(&[1, 2]));
+    expect_value(&mut universe, "#(1 2 3 4) last: 2", array(&[3, 4]));
+    expect_value(&mut universe, "#(1 2 3) first: 100", array(&[1, 2, 3]));
+    expect_value(&mut universe, "#(1 2 3) last: 100", array(&[1, 2, 3]));
+    expect_value(&mut universe, "#(1 2 3) first: -1", array(&[]));
+    expect_value(&mut universe, "#(1 2 3) last: -1", array(&[]));
+}
+
+#[test]
+fn system_environment_variable() {
+    let mut universe = setup_universe();
+
+    std::env::set_var("SOM_AST_PRIMITIVE_TEST_VAR", "hello from the environment");
+    std::env::remove_var("SOM_AST_PRIMITIVE_TEST_UNSET");
+
+    som_interpreter_ast::primitives::set_allow_environment(false);
+    match evaluate(
+        &mut universe,
+        "system environmentVariable: 'SOM_AST_PRIMITIVE_TEST_VAR'",
+    ) {
+        Return::Exception(err) => assert!(err.contains("disabled"), "{}", err),
+        _ => panic!("expected an exception when the environment is not accessible"),
+    }
+
+    som_interpreter_ast::primitives::set_allow_environment(true);
+    expect_value(
+        &mut universe,
+        "system environmentVariable: 'SOM_AST_PRIMITIVE_TEST_VAR'",
+        Value::String(Rc::new(String::from("hello from the environment"))),
+    );
+    expect_value(
+        &mut universe,
+        "system environmentVariable: 'SOM_AST_PRIMITIVE_TEST_UNSET'",
+        Value::Nil,
+    );
+}
+
+#[test]
+fn string_print_string() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    expect_value(&mut universe, "'hi' printString", string("'hi'"));
+    expect_value(&mut universe, "'hi' asString", string("hi"));
+    expect_value(&mut universe, r"'it\'s' printString", string(r"'it\'s'"));
+    expect_value(&mut universe, r"'a\\' printString", string(r"'a\\'"));
+    expect_value(&mut universe, "#foo printString", string("#foo"));
+    expect_value(&mut universe, "#foo asString", string("foo"));
+    expect_value(&mut universe, "42 printString", string("42"));
+}
+
+#[test]
+fn string_occurrences_of() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "'ababa' occurrencesOf: 'aba'",
+        Value::Integer(1),
+    );
+    expect_value(
+        &mut universe,
+        "'abcabcabc' occurrencesOf: 'bc'",
+        Value::Integer(3),
+    );
+    expect_value(&mut universe, "'abc' occurrencesOf: 'x'", Value::Integer(0));
+    expect_value(&mut universe, "'abc' occurrencesOf: ''", Value::Integer(0));
+}
+
+#[test]
+fn integer_radix_strings() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    expect_value(&mut universe, "0 asString: 2", string("0"));
+    expect_value(&mut universe, "-10 asString: 2", string("-1010"));
+    expect_value(&mut universe, "255 printString: 16", string("ff"));
+    expect_value(&mut universe, "0 printString: 16", string("0"));
+    expect_value(&mut universe, "-10 printString: 2", string("-1010"));
+
+    match evaluate(&mut universe, "10 asString: 1") {
+        Return::Exception(err) => assert!(err.contains("invalid radix"), "{}", err),
+        _ => panic!("expected an exception for an invalid radix"),
+    }
+}
+
+#[test]
+fn array_occurrences_of() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "#(1 2 1 3 1) occurrencesOf: 1",
+        Value::Integer(3),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 #foo 'bar' #foo) occurrencesOf: #foo",
+        Value::Integer(2),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) occurrencesOf: 4",
+        Value::Integer(0),
+    );
+}
+
+#[test]
+fn double_as_string_with_decimals() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    expect_value(
+        &mut universe,
+        "3.14159 asStringWithDecimals: 2",
+        string("3.14"),
+    );
+    expect_value(
+        &mut universe,
+        "2.5 asStringWithDecimals: 3",
+        string("2.500"),
+    );
+    expect_value(&mut universe, "3.7 asStringWithDecimals: -1", string("4"));
+    expect_value(
+        &mut universe,
+        "(0.0 // 0.0) asStringWithDecimals: 2",
+        string("nan"),
+    );
+    expect_value(
+        &mut universe,
+        "(1.0 // 0.0) asStringWithDecimals: 2",
+        string("inf"),
+    );
+    for decimals in ["1074", "70000", "1000000000000000"].iter() {
+        expect_value(
+            &mut universe,
+            &format!("1.5 asStringWithDecimals: {}", decimals),
+            string(&format!("1.5{}", "0".repeat(1073))),
+        );
+    }
+}
+
+#[test]
+fn object_nil_checks() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "nil isNil", Value::Boolean(true));
+    expect_value(&mut universe, "nil notNil", Value::Boolean(false));
+    expect_value(&mut universe, "3 isNil", Value::Boolean(false));
+    expect_value(&mut universe, "3 notNil", Value::Boolean(true));
+
+    for selector in ["isNil", "notNil"].iter() {
+        let method = universe
+            .core
+            .object_class
+            .borrow()
+            .lookup_method(selector)
+            .expect("method not found");
+        assert!(
+            method.is_primitive(),
+            "Object>>#{} is not bound to its primitive",
+            selector
+        );
+    }
+}
+
+#[test]
+fn array_all_any_satisfy() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "#(2 4 6) allSatisfy: [ :x | x % 2 = 0 ]",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "#(2 3 6) allSatisfy: [ :x | x % 2 = 0 ]",
+        Value::Boolean(false),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 3 4) anySatisfy: [ :x | x % 2 = 0 ]",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 3 5) anySatisfy: [ :x | x % 2 = 0 ]",
+        Value::Boolean(false),
+    );
+    expect_value(
+        &mut universe,
+        "#() allSatisfy: [ :x | false ]",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "#() anySatisfy: [ :x | true ]",
+        Value::Boolean(false),
+    );
+
+    for (expr, expected) in [
+        ("#(1 2 3) anySatisfy: [ :x | count := count + 1. x = 1 ]", 1),
+        ("#(1 2 3) allSatisfy: [ :x | count := count + 1. x > 1 ]", 1),
+    ]
+    .iter()
+    {
+        match evaluate_method(
+            &mut universe,
+            &format!("| count | count := 0. {}. ^ count", expr),
+        ) {
+            Return::Local(output) => {
+                assert_eq!(output, Value::Integer(*expected), "for '{}'", expr)
+            }
+            _ => panic!("unexpected non-local result"),
+        }
+    }
+}
+
+#[test]
+fn string_index_of_sub_collection() {
+    let mut universe = setup_universe();
+
+    for selector in ["indexOf:", "indexOfSubCollection:"].iter() {
+        let cases = [
+            ("'hello world' {} 'world'", 7),
+            ("'hello world' {} 'o'", 5),
+            ("'hello world' {} 'xyz'", 0),
+            ("'hello' {} ''", 0),
+            ("'héllo wörld' {} 'wörld'", 7),
+        ];
+        for (template, expected) in cases.iter() {
+            let expr = template.replace("{}", selector);
+            expect_value(&mut universe, &expr, Value::Integer(*expected));
+        }
+    }
+}
+
+#[test]
+fn number_abs() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "-5 abs", Value::Integer(5));
+    expect_value(&mut universe, "5 abs", Value::Integer(5));
+    expect_value(
+        &mut universe,
+        "(0 - 9223372036854775807 - 1) abs",
+        Value::BigInteger(BigInt::from(i64::MAX) + 1),
+    );
+    expect_value(
+        &mut universe,
+        "(0 - 9223372036854775808) abs",
+        Value::BigInteger(BigInt::from(i64::MAX) + 1),
+    );
+    expect_value(
+        &mut universe,
+        "(0 - 9223372036854775807) abs",
+        Value::Integer(i64::MAX),
+    );
+    expect_value(&mut universe, "-2.5 abs", Value::Double(2.5));
+}
+
+#[test]
+fn class_includes_behavior() {
+    let mut universe = setup_universe();
+
+    let cases = [
+        ("Integer includesBehavior: Object", true),
+        ("Object includesBehavior: Integer", false),
+        ("Integer includesBehavior: Integer", true),
+        ("Integer includesBehavior: String", false),
+        ("Integer class includesBehavior: Object class", true),
+        ("Object class includesBehavior: Integer class", false),
+        ("Integer includesBehavior: Integer class", false),
+    ];
+    for (expr, expected) in cases.iter() {
+        expect_value(&mut universe, expr, Value::Boolean(*expected));
+    }
+}
+
+#[test]
+fn array_with_index_collect() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "#(10 20 30) withIndexCollect: [ :e :i | e + i ]",
+        Value::Array(Rc::new(RefCell::new(vec![
+            Value::Integer(11),
+            Value::Integer(22),
+            Value::Integer(33),
+        ]))),
+    );
+    expect_value(
+        &mut universe,
+        "(#() withIndexCollect: [ :e :i | e ]) length",
+        Value::Integer(0),
+    );
+
+    let early = universe.intern_symbol("early");
+    match evaluate_method(
+        &mut universe,
+        "#(1 2 3) withIndexCollect: [ :e :i | i = 2 ifTrue: [ ^ #early ]. e ]. ^ #late",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(early)),
+        Return::NonLocal(_, _) => panic!("non-local return escaped its method"),
+        Return::Restart => panic!("unexpected `restart`"),
+        Return::Exception(err) => panic!("unexpected exception: '{}'", err),
+    }
+}
+
+#[test]
+fn symbol_equality() {
+    let mut universe = setup_universe();
+
+    let cases = [
+        ("#foo = #foo", true),
+        ("#foo = #bar", false),
+        ("#foo = 'foo'", false),
+        ("#foo = 'foo' asSymbol", true),
+        ("#foo = 3", false),
+        ("#foo = nil", false),
+    ];
+    for (expr, expected) in cases.iter() {
+        expect_value(&mut universe, expr, Value::Boolean(*expected));
+    }
+}
+
+#[test]
+fn string_do() {
+    let mut universe = setup_universe();
+
+    match evaluate_method(
+        &mut universe,
+        "| result | result := ''. 'héllo' do: [ :ch | result := result concatenate: ch ]. ^ result",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::String(Rc::new(String::from("héllo")))),
+        _ => panic!("unexpected non-local result"),
+    }
+    match evaluate_method(
+        &mut universe,
+        "| count | count := 0. 'abc' do: [ :ch | count := count + ch length ]. ^ count",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Integer(3)),
+        _ => panic!("unexpected non-local result"),
+    }
+
+    let early = universe.intern_symbol("early");
+    match evaluate_method(
+        &mut universe,
+        "'abc' do: [ :ch | ch = 'b' ifTrue: [ ^ #early ] ]. ^ #late",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(early)),
+        Return::NonLocal(_, _) => panic!("non-local return escaped its method"),
+        Return::Restart => panic!("unexpected `restart`"),
+        Return::Exception(err) => panic!("unexpected exception: '{}'", err),
+    }
+}
+
+#[test]
+fn integer_as_big_integer() {
+    let mut universe = setup_universe();
+
+    let integer_class = Value::Class(universe.core.integer_class.clone());
+
+    expect_value(
+        &mut universe,
+        "5 asBigInteger",
+        Value::BigInteger(BigInt::from(5)),
+    );
+    expect_value(
+        &mut universe,
+        "5 asBigInteger asBigInteger",
+        Value::BigInteger(BigInt::from(5)),
+    );
+    expect_value(&mut universe, "5 asBigInteger = 5", Value::Boolean(true));
+    expect_value(&mut universe, "5 = 5 asBigInteger", Value::Boolean(true));
+    expect_value(&mut universe, "5 asBigInteger class", integer_class);
+}
+
+#[test]
+fn array_at_if_absent() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "#(1 2 3) at: 2 ifAbsent: [ 42 ]",
+        Value::Integer(2),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) at: 4 ifAbsent: [ 42 ]",
+        Value::Integer(42),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) at: 0 ifAbsent: [ 42 ]",
+        Value::Integer(42),
+    );
+    match evaluate_method(
+        &mut universe,
+        "| count | count := 0. #(1 2 3) at: 1 ifAbsent: [ count := count + 1 ]. ^ count",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Integer(0)),
+        _ => panic!("unexpected non-local result"),
+    }
+}
+
+#[test]
+fn number_negated() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "5 negated", Value::Integer(-5));
+    expect_value(&mut universe, "5 negated = -5", Value::Boolean(true));
+    expect_value(
+        &mut universe,
+        "(0 - 9223372036854775807 - 1) negated",
+        Value::BigInteger(BigInt::from(i64::MAX) + 1),
+    );
+    expect_value(
+        &mut universe,
+        "9223372036854775808 negated",
+        Value::Integer(i64::MIN),
+    );
+    expect_value(&mut universe, "2.5 negated", Value::Double(-2.5));
+    expect_value(&mut universe, "2.5 negated = -2.5", Value::Boolean(true));
+}
+
+#[test]
+fn array_remove_all() {
+    let mut universe = setup_universe();
+
+    let array = |values: &[i64]| {
+        Value::Array(Rc::new(RefCell::new(
+            values.iter().copied().map(Value::Integer).collect(),
+        )))
+    };
+
+    expect_value(&mut universe, "#(1 2 1 3) removeAll: 1", array(&[2, 3]));
+    expect_value(&mut universe, "#(1 2 3) removeAll: 4", array(&[1, 2, 3]));
+    expect_value(&mut universe, "#(1 1) removeAll: 1", array(&[]));
+
+    match evaluate_method(
+        &mut universe,
+        "| values | values := #(1 2 1 3). values removeAll: 1. ^ values",
+    ) {
+        Return::Local(output) => assert_eq!(output, array(&[1, 2, 1, 3])),
+        _ => panic!("unexpected non-local result"),
+    }
+}
+
+#[test]
+fn integer_print_string_justified() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    expect_value(
+        &mut universe,
+        "7 printStringRightJustified: 4",
+        string("   7"),
+    );
+    expect_value(
+        &mut universe,
+        "7 printStringLeftJustified: 4",
+        string("7   "),
+    );
+    expect_value(
+        &mut universe,
+        "-42 printStringRightJustified: 5",
+        string("  -42"),
+    );
+    expect_value(
+        &mut universe,
+        "12345 printStringRightJustified: 2",
+        string("12345"),
+    );
+    expect_value(
+        &mut universe,
+        "12345 printStringLeftJustified: -1",
+        string("12345"),
+    );
+    expect_value(
+        &mut universe,
+        "5 printStringRightJustified: 70000",
+        string(&format!("{}5", " ".repeat(69_999))),
+    );
+    match evaluate(&mut universe, "5 printStringRightJustified: 1000000000000000") {
+        Return::Exception(message) => assert_eq!(
+            message,
+            "'Integer>>#printStringRightJustified:': the width exceeds the maximum of 16777216 characters"
+        ),
+        _ => panic!("expected an exception"),
+    }
+}
+
+#[test]
+fn string_matches_regex() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: 'h*o'",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: 'h*x'",
+        Value::Boolean(false),
+    );
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: 'h?llo'",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: 'hell'",
+        Value::Boolean(false),
+    );
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: '[a-h]ello'",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: '[!a-h]ello'",
+        Value::Boolean(false),
+    );
+    expect_value(
+        &mut universe,
+        "'a*b' matchesRegex: 'a\\\\*b'",
+        Value::Boolean(true),
+    );
+    expect_value(
+        &mut universe,
+        "'axb' matchesRegex: 'a\\\\*b'",
+        Value::Boolean(false),
+    );
+    expect_value(&mut universe, "'' matchesRegex: '*'", Value::Boolean(true));
+    expect_value(&mut universe, "'hello' matchesRegex: '[a-z'", Value::Nil);
+    expect_value(
+        &mut universe,
+        "'hello' matchesRegex: '[z-a]ello'",
+        Value::Nil,
+    );
+}
+
+#[test]
+fn block_value_with_timeout() {
+    let mut universe = setup_universe();
+
+    expect_value(
+        &mut universe,
+        "[ 42 ] valueWithTimeout: 1000",
+        Value::Integer(42),
+    );
+
+    match evaluate_method(
+        &mut universe,
+        "[ [ true ] whileTrue: [ ] ] valueWithTimeout: 20. ^ #late",
+    ) {
+        Return::Exception(err) => assert_eq!(err, "block exceeded its time budget of 20 ms"),
+        _ => panic!("the timed block was not aborted"),
+    }
+
+    expect_value(
+        &mut universe,
+        "[ | count | count := 0. [ count < 3 ] whileTrue: [ count := count + 1 ]. count ] valueWithTimeout: 1000",
+        Value::Integer(3),
+    );
+}
+
+#[test]
+fn array_replace_from_to_with() {
+    let mut universe = setup_universe();
+
+    let array = |values: &[i64]| {
+        Value::Array(Rc::new(RefCell::new(
+            values.iter().copied().map(Value::Integer).collect(),
+        )))
+    };
+
+    expect_value(
+        &mut universe,
+        "#(1 2 3 4 5) replaceFrom: 2 to: 4 with: #(7 8 9)",
+        array(&[1, 7, 8, 9, 5]),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) replaceFrom: 1 to: 1 with: #(7 8 9)",
+        array(&[7, 2, 3]),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) replaceFrom: 2 to: 1 with: #()",
+        array(&[1, 2, 3]),
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) replaceFrom: 2 to: 4 with: #(7 8 9)",
+        Value::Nil,
+    );
+    expect_value(
+        &mut universe,
+        "#(1 2 3) replaceFrom: 0 to: 2 with: #(7 8 9)",
+        Value::Nil,
+    );
+
+    match evaluate_method(
+        &mut universe,
+        "| values | values := #(1 2 3). values replaceFrom: 1 to: 3 with: #(7 8). ^ values",
+    ) {
+        Return::Local(output) => assert_eq!(output, array(&[1, 2, 3])),
+        _ => panic!("unexpected non-local result"),
+    }
+    match evaluate_method(
+        &mut universe,
+        "| values | values := #(1 2 3). values replaceFrom: 2 to: 3 with: values. ^ values",
+    ) {
+        Return::Local(output) => assert_eq!(output, array(&[1, 1, 2])),
+        _ => panic!("unexpected non-local result"),
+    }
+}
+
+#[test]
+fn object_as_string() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.core.object_class.clone();
+    load_class(&mut universe, "Point = ( | x y | )", &object_class);
+    load_class(&mut universe, "Account = ( )", &object_class);
+
+    let tests = &[
+        ("Point new asString", "a Point"),
+        ("Account new asString", "an Account"),
+        ("Object new asString", "an Object"),
+        ("42 asString", "42"),
+        ("nil asString", "nil"),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(
+            &mut universe,
+            expr,
+            Value::String(Rc::new(expected.to_string())),
+        );
+    }
+}
+
+#[test]
+fn universe_load_class_from_source() {
+    let mut universe = setup_universe();
+
+    universe
+        .load_class_from_source("Greeter = ( greet = ( ^'hello, world' ) )")
+        .expect("could not load class from source");
+    universe
+        .load_class_from_source("LoudGreeter = Greeter ( greet = ( ^super greet + '!' ) )")
+        .expect("could not load subclass from source");
+
+    expect_value(
+        &mut universe,
+        "Greeter new greet",
+        Value::String(Rc::new(String::from("hello, world"))),
+    );
+    expect_value(
+        &mut universe,
+        "LoudGreeter new greet",
+        Value::String(Rc::new(String::from("hello, world!"))),
+    );
+
+    let err = universe
+        .load_class_from_source("Orphan = MissingParent ( )")
+        .expect_err("loaded a class with a missing superclass");
+    assert_eq!(
+        err.to_string(),
+        "could not find the 'MissingParent' class (superclass of 'Orphan')"
+    );
+    assert!(universe.load_class_from_source("Broken = (").is_err());
+}
+
+#[test]
+fn double_inverse_trigonometry() {
+    let mut universe = setup_universe();
+
+    let mut double = |expr: &str| match evaluate(&mut universe, expr) {
+        Return::Local(Value::Double(value)) => value,
+        Return::Local(value) => panic!("unexpected value for '{}': {:?}", expr, value),
+        _ => panic!("unexpected non-local result for '{}'", expr),
+    };
+
+    let tests = &[
+        ("1.0 arcTan: 1.0", std::f64::consts::FRAC_PI_4),
+        ("1.0 arcTan: -1.0", 3.0 * std::f64::consts::FRAC_PI_4),
+        ("1.0 arcTan", std::f64::consts::FRAC_PI_4),
+        ("1.0 arcSin", std::f64::consts::FRAC_PI_2),
+        ("1.0 arcCos", 0.0),
+        ("0.5 arcCos", std::f64::consts::FRAC_PI_3),
+    ];
+
+    for (expr, expected) in tests {
+        let value = double(expr);
+        assert!(
+            (value - expected).abs() < 1e-12,
+            "unexpected result for '{}': {}",
+            expr,
+            value
+        );
+    }
+
+    assert!(double("2.0 arcSin").is_nan());
+    assert!(double("-2.0 arcCos").is_nan());
+}
+
+#[test]
+fn string_pad_with() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("'7' leftPad: 3 with: '0'", "007"),
+        ("'7' rightPad: 3 with: '.'", "7.."),
+        ("'hello' leftPad: 3 with: ' '", "hello"),
+        ("'abc' rightPad: 3 with: '-'", "abc"),
+        ("'été' leftPad: 4 with: '*'", "*été"),
+        ("#sym rightPad: 5 with: '_'", "sym__"),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(
+            &mut universe,
+            expr,
+            Value::String(Rc::new(expected.to_string())),
+        );
+    }
+
+    match evaluate(&mut universe, "'7' leftPad: 1000000000000000 with: '0'") {
+        Return::Exception(message) => assert_eq!(
+            message,
+            "'String>>#leftPad:with:': the width exceeds the maximum of 16777216 characters"
+        ),
+        _ => panic!("expected an exception"),
+    }
+}
+
+#[test]
+fn smalltalk_global_dictionary() {
+    let mut universe = setup_universe();
+
+    let integer_class = universe.core.integer_class.clone();
+    expect_value(
+        &mut universe,
+        "Smalltalk at: #Integer",
+        Value::Class(integer_class),
+    );
+    expect_value(&mut universe, "Smalltalk at: #NoSuchGlobal", Value::Nil);
+    match evaluate_method(
+        &mut universe,
+        "Smalltalk at: #Answer put: 42. ^ (Smalltalk at: #Answer) + (system global: #Answer)",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Integer(84)),
+        _ => panic!("unexpected non-local result"),
+    }
+}
+
+#[test]
+fn array_shuffle() {
+    let mut universe = setup_universe();
+
+    let mut shuffled = |body: &str| match evaluate_method(&mut universe, body) {
+        Return::Local(Value::Array(values)) => values.borrow().clone(),
+        Return::Local(value) => panic!("unexpected value: {:?}", value),
+        _ => panic!("unexpected non-local result"),
+    };
+
+    let setup = "| a | a := Array new: 20. 1 to: 20 do: [ :i | a at: i put: i ]. ";
+    let first = shuffled(&format!("{} ^ a shuffle: 7", setup));
+    let second = shuffled(&format!("{} ^ a shuffle: 7", setup));
+    let other = shuffled(&format!("{} ^ a shuffle: 8", setup));
+    let receiver = shuffled(&format!("{} a shuffle: 7. ^ a", setup));
+
+    let original: Vec<_> = (1..=20).map(Value::Integer).collect();
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+    assert_ne!(first, original);
+    assert_eq!(receiver, original);
+
+    let mut sorted = first;
+    sorted.sort_by_key(|value| match value {
+        Value::Integer(value) => *value,
+        value => panic!("unexpected element: {:?}", value),
+    });
+    assert_eq!(sorted, original);
+}
+
+#[test]
+fn string_comparison() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("'abc' < 'abd'", true),
+        ("'b' < 'a'", false),
+        ("'ab' < 'abc'", true),
+        ("'abc' < 'abc'", false),
+        ("'abc' <= 'abc'", true),
+        ("'b' <= 'a'", false),
+        ("'b' > 'a'", true),
+        ("'a' > 'a'", false),
+        ("'a' >= 'a'", true),
+        ("'Z' < 'a'", true),
+        ("#abc < 'abd'", true),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(&mut universe, expr, Value::Boolean(*expected));
+    }
+}
+
+#[test]
+fn string_comparison_sorts_strings() {
+    let mut universe = setup_universe();
+
+    // The core library has no `asSortedArray`, so this insertion-sorts through `String>>#<`.
+    match evaluate_method(
+        &mut universe,
+        "| words sorted | words := #('pear' 'apple' 'fig' 'banana' 'apricot'). 2 to: words length do: [ :i | | word j | word := words at: i. j := i - 1. [ j > 0 and: [ word < (words at: j) ] ] whileTrue: [ words at: j + 1 put: (words at: j). j := j - 1 ]. words at: j + 1 put: word ]. sorted := ''. words do: [ :word | sorted := sorted + word + ' ' ]. ^ sorted",
+    ) {
+        Return::Local(output) => assert_eq!(
+            output,
+            Value::String(Rc::new(String::from("apple apricot banana fig pear ")))
+        ),
+        Return::NonLocal(_, _) => panic!("unexpected non-local return"),
+        Return::Restart => panic!("unexpected `restart`"),
+        Return::Exception(err) => panic!("unexpected exception: '{}'", err),
+    }
+}
+
+#[test]
+fn universe_install_class_extension() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.core.object_class.clone();
+    load_class(
+        &mut universe,
+        "Counter = ( | count | reset = ( count := 5 ) )",
+        &object_class,
+    );
+
+    universe
+        .install_class_extension("Integer", "double = ( ^self * 2 ) ---- answer = ( ^42 )")
+        .expect("could not install class extension");
+    universe
+        .install_class_extension("Integer", "abs = ( ^7 )")
+        .expect("could not install class extension");
+    universe
+        .install_class_extension("Counter", "count = ( ^count )")
+        .expect("could not install class extension");
+
+    expect_value(&mut universe, "21 double", Value::Integer(42));
+    expect_value(&mut universe, "Integer answer", Value::Integer(42));
+    expect_value(&mut universe, "-3 abs", Value::Integer(7));
+    match evaluate_method(&mut universe, "| c | c := Counter new. c reset. ^ c count") {
+        Return::Local(output) => assert_eq!(output, Value::Integer(5)),
+        _ => panic!("unexpected non-local result"),
+    }
+
+    assert!(universe
+        .install_class_extension("Counter", "| extra | extra = ( ^extra )")
+        .is_err());
+    assert!(universe
+        .install_class_extension("NoSuchClass", "foo = ( ^1 )")
+        .is_err());
+}
+
+#[test]
+fn double_equality() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("2.0 = 2", true),
+        ("2.5 = 2", false),
+        ("2.0 = 2.0", true),
+        ("100000000000000000000.0 = 100000000000000000000", true),
+        ("2.0 = '2'", false),
+        ("2.0 = nil", false),
+        ("(Double PositiveInfinity - Double PositiveInfinity) = (Double PositiveInfinity - Double PositiveInfinity)", false),
+        ("(Double PositiveInfinity - Double PositiveInfinity) = 0", false),
+        ("Double PositiveInfinity = Double PositiveInfinity", true),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(&mut universe, expr, Value::Boolean(*expected));
+    }
+}
+
+#[test]
+fn system_load_module() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("(system loadModule: #Array) == Array", Value::Boolean(true)),
+        (
+            "((system loadModule: #Array) new: 3) length",
+            Value::Integer(3),
+        ),
+        (
+            "((system loadModule: 'Array') new: 2) length",
+            Value::Integer(2),
+        ),
+        ("system loadModule: #NoSuchModule", Value::Nil),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(&mut universe, expr, expected.clone());
+    }
+}
+
+#[test]
+fn array_partition() {
+    let mut universe = setup_universe();
+
+    fn array(values: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(values)))
+    }
+
+    expect_value(
+        &mut universe,
+        "#(1 2 3 4) partition: [ :e | e % 2 = 0 ]",
+        array(vec![
+            array(vec![Value::Integer(2), Value::Integer(4)]),
+            array(vec![Value::Integer(1), Value::Integer(3)]),
+        ]),
+    );
+    expect_value(
+        &mut universe,
+        "#() partition: [ :e | true ]",
+        array(vec![array(vec![]), array(vec![])]),
+    );
+
+    match evaluate(&mut universe, "#(1 2) partition: [ :e | e ]") {
+        Return::Exception(message) => assert_eq!(
+            message,
+            "'Array>>#partition:': the block did not return a boolean"
+        ),
+        _ => panic!("expected an exception"),
+    }
+}
+
+#[test]
+fn integer_as_string_cache() {
+    let mut universe = setup_universe();
+
+    let as_string = |universe: &mut Universe, expr: &str| match evaluate(universe, expr) {
+        Return::Local(Value::String(value)) => value,
+        _ => panic!("unexpected result for '{}'", expr),
+    };
+
+    let first = as_string(&mut universe, "5 asString");
+    let second = as_string(&mut universe, "5 asString");
+    assert_eq!(first.as_str(), "5");
+    assert!(Rc::ptr_eq(&first, &second));
+
+    let first = as_string(&mut universe, "1000 asString");
+    let second = as_string(&mut universe, "1000 asString");
+    assert_eq!(first, second);
+    assert!(!Rc::ptr_eq(&first, &second));
+}
+
+#[test]
+fn does_not_understand_proxy() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.core.object_class.clone();
+    load_class(
+        &mut universe,
+        "Proxy = ( | selector arguments | doesNotUnderstand: sel arguments: args = ( selector := sel. arguments := args. ^ #sentinel ) selector = ( ^ selector ) arguments = ( ^ arguments ) )",
+        &object_class,
+    );
+
+    let sentinel = universe.intern_symbol("sentinel");
+    let frobnicate = universe.intern_symbol("frobnicate");
+    let at_put = universe.intern_symbol("at:put:");
+    expect_value(
+        &mut universe,
+        "Proxy new frobnicate",
+        Value::Symbol(sentinel),
+    );
+    match evaluate_method(
+        &mut universe,
+        "| p | p := Proxy new. p frobnicate. ^ p selector",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(frobnicate)),
+        _ => panic!("expected a value"),
+    }
+    match evaluate_method(
+        &mut universe,
+        "| p | p := Proxy new. p at: 1 put: 2. ^ p selector",
+    ) {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(at_put)),
+        _ => panic!("expected a value"),
+    }
+    match evaluate_method(
+        &mut universe,
+        "| p | p := Proxy new. p at: 1 put: 2. ^ p arguments",
+    ) {
+        Return::Local(output) => assert_eq!(
+            output,
+            Value::Array(Rc::new(RefCell::new(vec![
+                Value::Integer(1),
+                Value::Integer(2)
+            ])))
+        ),
+        _ => panic!("expected a value"),
+    }
+    match evaluate_method(&mut universe, "| p | p := Proxy new. ^ p + 3") {
+        Return::Local(output) => assert_eq!(output, Value::Symbol(sentinel)),
+        _ => panic!("expected a value"),
+    }
+}
+
+#[test]
+fn array_remove_duplicates() {
+    let mut universe = setup_universe();
+
+    fn array(values: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(values)))
+    }
+
+    expect_value(
+        &mut universe,
+        "#(1 2 1 3 2) removeDuplicates",
+        array(vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(3),
+        ]),
+    );
+    expect_value(&mut universe, "#() removeDuplicates", array(vec![]));
+    match evaluate_method(&mut universe, "| a | a := #(1 1). a removeDuplicates. ^ a") {
+        Return::Local(output) => {
+            assert_eq!(output, array(vec![Value::Integer(1), Value::Integer(1)]))
+        }
+        _ => panic!("expected a value"),
+    }
+}
+
+#[test]
+fn string_join() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("', ' join: #('a' 'b' 'c')", "a, b, c"),
+        ("'-' join: #()", ""),
+        ("'' join: #('x' 'y')", "xy"),
+        ("', ' join: #('a' 1 2.5 #b)", "a, 1, 2.5, #b"),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(
+            &mut universe,
+            expr,
+            Value::String(Rc::new(expected.to_string())),
+        );
+    }
+}
+
+#[test]
+fn strict_globals() {
+    let mut universe = setup_universe();
+
+    match evaluate(&mut universe, "Strng") {
+        Return::Exception(message) => assert!(
+            !message.contains("strict globals"),
+            "unexpected error: {}",
+            message
+        ),
+        _ => panic!("expected an exception"),
+    }
+
+    let directory = std::env::temp_dir().join("som-interpreter-ast-strict-globals");
+    std::fs::create_dir_all(&directory).expect("could not create test directory");
+    std::fs::write(
+        directory.join("StrictHelper.som"),
+        "StrictHelper = ( ---- greet = ( ^ 42 ) )",
+    )
+    .expect("could not write test class");
+    universe.classpath.push(directory);
+
+    universe.strict_globals = true;
+    expect_value(&mut universe, "StrictHelper greet", Value::Integer(42));
+    match evaluate(&mut universe, "Strng") {
+        Return::Exception(message) => assert_eq!(
+            message,
+            "the global 'Strng' is not defined (strict globals are enabled)"
+        ),
+        _ => panic!("expected an exception"),
+    }
+    expect_value(&mut universe, "Array == Array", Value::Boolean(true));
+}
+
+#[test]
+fn integer_division() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("7 / 2", Value::Integer(3)),
+        ("-7 / 2", Value::Integer(-3)),
+        ("7 / -2", Value::Integer(-3)),
+        (
+            "(100000000000000000000 / 3) = 33333333333333333333",
+            Value::Boolean(true),
+        ),
+        ("7 // 2", Value::Double(3.5)),
+        ("-7 // 2", Value::Double(-3.5)),
+    ];
+
+    for (expr, expected) in tests {
+        expect_value(&mut universe, expr, expected.clone());
+    }
+}
+
+#[test]
+fn object_assert() {
+    let mut universe = setup_universe();
+
+    expect_value(&mut universe, "(3 assert: true) + 1", Value::Integer(4));
+    expect_value(
+        &mut universe,
+        "3 assert: 1 < 2 description: 'unused'",
+        Value::Integer(3),
+    );
+
+    // Failed assertions are reported through `error:`, which this class answers instead of exiting.
+    let object_class = universe.core.object_class.clone();
+    load_class(
+        &mut universe,
+        "Asserter = ( error: message = ( ^ message ) )",
+        &object_class,
+    );
+    expect_value(
+        &mut universe,
+        "Asserter new assert: false",
+        Value::String(Rc::new(String::from("assertion failed"))),
+    );
+    expect_value(
+        &mut universe,
+        "Asserter new assert: 1 = 2 description: 'one is not two'",
+        Value::String(Rc::new(String::from("assertion failed: one is not two"))),
+    );
+}
+
+#[test]
+fn missing_does_not_understand_reports_error() {
+    let mut universe = setup_universe();
+
+    // A class outside of the usual hierarchy, which does not inherit `Object>>#doesNotUnderstand:arguments:`,
+    // and whose `error:` answers the message instead of exiting.
+    let tokens: Vec<Token> = Lexer::new("Bare = ( error: message = ( ^ message ) )")
+        .skip_comments(true)
+        .skip_whitespace(true)
+        .collect();
+    let class_def = som_parser::parse_file(tokens.as_slice()).unwrap();
+    let bare_class = Class::from_class_def(class_def).expect("could not create test class");
+    let instance = Value::Instance(Rc::new(RefCell::new(Instance::from_class(bare_class))));
+
+    match universe.does_not_understand(instance, "foo", vec![]) {
+        Some(Return::Local(output)) => assert_eq!(
+            output,
+            Value::String(Rc::new(String::from(
+                "could not find method 'Bare>>#foo', and its receiver does not define 'doesNotUnderstand:arguments:'"
+            )))
+        ),
+        Some(Return::NonLocal(_, _)) => panic!("unexpected non-local return"),
+        Some(Return::Restart) => panic!("unexpected `restart`"),
+        Some(Return::Exception(err)) => panic!("unexpected exception: '{}'", err),
+        None => panic!("`error:` was not sent"),
+    }
+}
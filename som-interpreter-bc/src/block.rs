@@ -1,4 +1,6 @@
+use std::cell::OnceCell;
 use std::fmt;
+use std::rc::Rc;
 
 use som_core::bytecode::Bytecode;
 
@@ -17,6 +19,11 @@ pub struct Block {
     pub locals: Vec<Value>,
     pub literals: Vec<Literal>,
     pub body: Vec<Bytecode>,
+    /// The resolved values of the globals pushed by `PushGlobalCached`, indexed like the literals.
+    pub global_cache: Rc<Vec<OnceCell<Value>>>,
+    /// The operand stack height expected before each bytecode, indexed like the body.
+    #[cfg(feature = "stack-maps")]
+    pub stack_map: Vec<usize>,
     pub nb_params: usize,
 }
 
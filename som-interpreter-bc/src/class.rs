@@ -33,6 +33,8 @@ pub struct Class {
     pub methods: IndexMap<Interned, Rc<Method>>,
     /// Is this class a static one ?
     pub is_static: bool,
+    /// The comment documenting this class, if any.
+    pub comment: Option<String>,
 }
 
 impl Class {
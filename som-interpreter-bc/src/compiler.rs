@@ -1,7 +1,7 @@
 //!
 //! This is the bytecode compiler for the Simple Object Machine.
 //!
-use std::cell::RefCell;
+use std::cell::{OnceCell, RefCell};
 use std::hash::{Hash, Hasher};
 use std::rc::{Rc, Weak};
 
@@ -15,9 +15,41 @@ use crate::block::Block;
 use crate::class::{Class, MaybeWeak};
 use crate::interner::{Interned, Interner};
 use crate::method::{Method, MethodEnv, MethodKind};
+use crate::primitives;
 use crate::value::Value;
 use crate::SOMRef;
 
+/// The globals bound by the universe when bootstrapping, which programs cannot rebind.
+///
+/// References to these are compiled to `PushGlobalCached`, which resolves them only once,
+/// so `System>>#global:put:` refuses to assign them.
+pub(crate) const WELL_KNOWN_GLOBALS: &[&str] = &[
+    "nil",
+    "true",
+    "false",
+    "system",
+    "Smalltalk",
+    "Object",
+    "Class",
+    "Metaclass",
+    "Nil",
+    "Integer",
+    "Array",
+    "Method",
+    "Symbol",
+    "Primitive",
+    "String",
+    "System",
+    "Double",
+    "Boolean",
+    "True",
+    "False",
+    "Block",
+    "Block1",
+    "Block2",
+    "Block3",
+];
+
 #[derive(Debug, Clone)]
 pub enum Literal {
     Symbol(Interned),
@@ -90,6 +122,8 @@ enum FoundVar {
 trait GenCtxt {
     fn find_var(&mut self, name: &str) -> Option<FoundVar>;
     fn intern_symbol(&mut self, name: &str) -> Interned;
+    #[cfg(feature = "stack-maps")]
+    fn lookup_symbol(&self, symbol: Interned) -> &str;
     fn class_name(&self) -> &str;
 }
 
@@ -131,6 +165,11 @@ impl GenCtxt for BlockGenCtxt<'_> {
         self.outer.intern_symbol(name)
     }
 
+    #[cfg(feature = "stack-maps")]
+    fn lookup_symbol(&self, symbol: Interned) -> &str {
+        self.outer.lookup_symbol(symbol)
+    }
+
     fn class_name(&self) -> &str {
         self.outer.class_name()
     }
@@ -178,6 +217,11 @@ impl GenCtxt for MethodGenCtxt<'_> {
         self.inner.intern_symbol(name)
     }
 
+    #[cfg(feature = "stack-maps")]
+    fn lookup_symbol(&self, symbol: Interned) -> &str {
+        self.inner.lookup_symbol(symbol)
+    }
+
     fn class_name(&self) -> &str {
         self.inner.class_name()
     }
@@ -231,9 +275,14 @@ impl MethodCodegen for ast::Expression {
                     }
                     Some(FoundVar::Field(idx)) => ctxt.push_instr(Bytecode::PushField(idx)),
                     None => {
+                        let cacheable = WELL_KNOWN_GLOBALS.contains(&name.as_str());
                         let name = ctxt.intern_symbol(name);
                         let idx = ctxt.push_literal(Literal::Symbol(name));
-                        ctxt.push_instr(Bytecode::PushGlobal(idx as u8));
+                        if cacheable {
+                            ctxt.push_instr(Bytecode::PushGlobalCached(idx as u8));
+                        } else {
+                            ctxt.push_instr(Bytecode::PushGlobal(idx as u8));
+                        }
                     }
                 }
                 Some(())
@@ -348,6 +397,11 @@ impl GenCtxt for ClassGenCtxt<'_> {
         self.interner.intern(name)
     }
 
+    #[cfg(feature = "stack-maps")]
+    fn lookup_symbol(&self, symbol: Interned) -> &str {
+        self.interner.lookup(symbol)
+    }
+
     fn class_name(&self) -> &str {
         self.name.as_str()
     }
@@ -406,9 +460,19 @@ fn compile_method(outer: &mut dyn GenCtxt, defn: &ast::MethodDef) -> Option<Meth
             ),
             // ast::MethodBody::Primitive => MethodKind::NotImplemented(defn.signature.clone()),
             ast::MethodBody::Body { .. } => {
+                #[cfg(feature = "stack-maps")]
+                let stack_map = compute_stack_map(
+                    &ctxt,
+                    &ctxt.inner.literals,
+                    ctxt.inner.body.as_deref().unwrap_or_default(),
+                );
+                let literals: Vec<_> = ctxt.inner.literals.into_iter().collect();
                 let env = MethodEnv {
                     locals: ctxt.inner.locals.iter().map(|_| Value::Nil).collect(),
-                    literals: ctxt.inner.literals.into_iter().collect(),
+                    global_cache: Rc::new(literals.iter().map(|_| OnceCell::new()).collect()),
+                    #[cfg(feature = "stack-maps")]
+                    stack_map,
+                    literals,
                     body: ctxt.inner.body.unwrap_or_default(),
                 };
                 MethodKind::Defined(env)
@@ -444,10 +508,20 @@ fn compile_block(outer: &mut dyn GenCtxt, defn: &ast::Block) -> Option<Block> {
         ctxt.push_instr(Bytecode::ReturnLocal);
     }
 
+    #[cfg(feature = "stack-maps")]
+    let stack_map = compute_stack_map(
+        &ctxt,
+        &ctxt.literals,
+        ctxt.body.as_deref().unwrap_or_default(),
+    );
+    let literals: Vec<_> = ctxt.literals.into_iter().collect();
     let block = Block {
         frame: None,
         locals: ctxt.locals.into_iter().map(|_| Value::Nil).collect(),
-        literals: ctxt.literals.into_iter().collect(),
+        global_cache: Rc::new(literals.iter().map(|_| OnceCell::new()).collect()),
+        #[cfg(feature = "stack-maps")]
+        stack_map,
+        literals,
         body: ctxt.body.unwrap_or_default(),
         nb_params: ctxt.args.len(),
     };
@@ -457,6 +531,90 @@ fn compile_block(outer: &mut dyn GenCtxt, defn: &ast::Block) -> Option<Block> {
     Some(block)
 }
 
+/// Get the number of arguments (excluding the receiver) taken by a message with the given signature.
+pub(crate) fn nb_params(signature: &str) -> usize {
+    match signature.chars().next() {
+        Some(ch) if !ch.is_alphabetic() => 1,
+        _ => signature.chars().filter(|ch| *ch == ':').count(),
+    }
+}
+
+/// Compute the operand stack height expected before each bytecode of the given body.
+#[cfg(feature = "stack-maps")]
+fn compute_stack_map(
+    ctxt: &dyn GenCtxt,
+    literals: &IndexSet<Literal>,
+    body: &[Bytecode],
+) -> Vec<usize> {
+    let mut height = 0usize;
+    let mut stack_map = Vec::with_capacity(body.len());
+    for bytecode in body {
+        stack_map.push(height);
+        height = match bytecode {
+            Bytecode::Halt | Bytecode::NilLocal(_) => height,
+            Bytecode::Dup
+            | Bytecode::PushLocal(_, _)
+            | Bytecode::PushArgument(_, _)
+            | Bytecode::PushField(_)
+            | Bytecode::PushBlock(_)
+            | Bytecode::PushConstant(_)
+            | Bytecode::PushGlobal(_)
+            | Bytecode::PushGlobalCached(_) => height + 1,
+            Bytecode::Pop
+            | Bytecode::PopLocal(_, _)
+            | Bytecode::PopArgument(_, _)
+            | Bytecode::PopField(_) => height.saturating_sub(1),
+            Bytecode::Pop2 => height.saturating_sub(2),
+            // The receiver and the arguments are replaced by the message's result.
+            Bytecode::Send(idx) | Bytecode::SuperSend(idx) => {
+                match literals.get_index(*idx as usize) {
+                    Some(Literal::Symbol(symbol)) => {
+                        height.saturating_sub(nb_params(ctxt.lookup_symbol(*symbol)))
+                    }
+                    _ => height,
+                }
+            }
+            // Whatever follows a return is unreachable.
+            Bytecode::ReturnLocal | Bytecode::ReturnNonLocal => height.saturating_sub(1),
+        };
+    }
+    stack_map
+}
+
+/// Compile the methods of a class extension, and add them to the given (already loaded) class,
+/// replacing the methods it already has with the same signatures.
+pub fn compile_class_extension(
+    interner: &mut Interner,
+    defn: &ast::ClassDef,
+    class: &SOMRef<Class>,
+) -> Option<()> {
+    fn install_methods(
+        interner: &mut Interner,
+        class: &SOMRef<Class>,
+        methods: &[ast::MethodDef],
+    ) -> Option<()> {
+        let mut ctxt = ClassGenCtxt {
+            name: class.borrow().name().to_string(),
+            fields: class.borrow().locals.keys().copied().collect(),
+            methods: IndexMap::new(),
+            interner,
+        };
+
+        for method in methods {
+            let mut method = compile_method(&mut ctxt, method)?;
+            let signature = ctxt.interner.intern(method.signature.as_str());
+            method.holder = Rc::downgrade(class);
+            ctxt.methods.insert(signature, Rc::new(method));
+        }
+
+        class.borrow_mut().methods.extend(ctxt.methods);
+        Some(())
+    }
+
+    install_methods(interner, class, &defn.instance_methods)?;
+    install_methods(interner, &class.borrow().class(), &defn.static_methods)
+}
+
 // println!("compiling '{}' ...", defn.name);
 pub fn compile_class(
     interner: &mut Interner,
@@ -480,11 +638,7 @@ pub fn compile_class(
         collect_static_locals(interner, &super_class.borrow().class(), &mut locals);
     }
 
-    locals.extend(
-        defn.static_locals
-            .iter()
-            .map(|name| interner.intern(name.as_str())),
-    );
+    locals.extend(interner.intern_all(&defn.static_locals));
 
     let mut static_class_ctxt = ClassGenCtxt {
         name: format!("{} class", defn.name),
@@ -500,6 +654,7 @@ pub fn compile_class(
         locals: IndexMap::new(),
         methods: IndexMap::new(),
         is_static: true,
+        comment: None,
     }));
 
     for method in &defn.static_methods {
@@ -539,11 +694,7 @@ pub fn compile_class(
         collect_instance_locals(interner, super_class, &mut locals);
     }
 
-    locals.extend(
-        defn.instance_locals
-            .iter()
-            .map(|name| interner.intern(name.as_str())),
-    );
+    locals.extend(interner.intern_all(&defn.instance_locals));
 
     let mut instance_class_ctxt = ClassGenCtxt {
         name: defn.name.clone(),
@@ -559,6 +710,7 @@ pub fn compile_class(
         locals: IndexMap::new(),
         methods: IndexMap::new(),
         is_static: false,
+        comment: defn.comment.clone(),
     }));
 
     for method in &defn.instance_methods {
@@ -572,6 +724,31 @@ pub fn compile_class(
             .insert(signature, Rc::new(method));
     }
 
+    let extensions = primitives::get_extensions(defn.name.as_str());
+    let symbols = instance_class_ctxt.interner.intern_all(extensions);
+    for (signature, symbol) in extensions.iter().zip(symbols) {
+        if instance_class_ctxt.methods.contains_key(&symbol) {
+            continue;
+        }
+        let method = Method {
+            kind: MethodKind::primitive_from_signature(defn.name.as_str(), signature),
+            holder: Rc::downgrade(&instance_class),
+            signature: signature.to_string(),
+        };
+        instance_class_ctxt.methods.insert(symbol, Rc::new(method));
+    }
+
+    let overrides = primitives::get_overrides(defn.name.as_str());
+    let symbols = instance_class_ctxt.interner.intern_all(overrides);
+    for (signature, symbol) in overrides.iter().zip(symbols) {
+        let method = Method {
+            kind: MethodKind::primitive_from_signature(defn.name.as_str(), signature),
+            holder: Rc::downgrade(&instance_class),
+            signature: signature.to_string(),
+        };
+        instance_class_ctxt.methods.insert(symbol, Rc::new(method));
+    }
+
     let mut instance_class_mut = instance_class.borrow_mut();
     instance_class_mut.locals = instance_class_ctxt
         .fields
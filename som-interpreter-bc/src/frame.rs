@@ -119,6 +119,19 @@ impl Frame {
         self.get_bytecode(self.bytecode_idx)
     }
 
+    /// Get the operand stack height expected before the current bytecode, as recorded by the compiler.
+    #[cfg(feature = "stack-maps")]
+    pub fn expected_stack_height(&self) -> Option<usize> {
+        let stack_map = match &self.kind {
+            FrameKind::Method { method, .. } => match method.kind() {
+                MethodKind::Defined(env) => &env.stack_map,
+                MethodKind::Primitive(_) | MethodKind::NotImplemented(_) => return None,
+            },
+            FrameKind::Block { block, .. } => &block.stack_map,
+        };
+        stack_map.get(self.bytecode_idx).copied()
+    }
+
     pub fn lookup_constant(&self, idx: usize) -> Option<Literal> {
         match self.kind() {
             FrameKind::Block { block } => block.literals.get(idx).cloned(),
@@ -130,6 +143,33 @@ impl Frame {
         }
     }
 
+    /// Get the cached value of the global named by the literal at the given index, if it has already been resolved.
+    pub fn lookup_cached_global(&self, idx: usize) -> Option<Value> {
+        match self.kind() {
+            FrameKind::Block { block } => block.global_cache.get(idx)?.get().cloned(),
+            FrameKind::Method { method, .. } => match method.kind() {
+                MethodKind::Defined(env) => env.global_cache.get(idx)?.get().cloned(),
+                MethodKind::Primitive(_) => None,
+                MethodKind::NotImplemented(_) => None,
+            },
+        }
+    }
+
+    /// Cache the resolved value of the global named by the literal at the given index.
+    pub fn cache_global(&self, idx: usize, value: Value) {
+        let cache = match self.kind() {
+            FrameKind::Block { block } => &block.global_cache,
+            FrameKind::Method { method, .. } => match method.kind() {
+                MethodKind::Defined(env) => &env.global_cache,
+                MethodKind::Primitive(_) | MethodKind::NotImplemented(_) => return,
+            },
+        };
+        if let Some(slot) = cache.get(idx) {
+            // The slot is never invalidated, so a value that is already there stays.
+            let _ = slot.set(value);
+        }
+    }
+
     pub fn lookup_argument(&self, idx: usize) -> Option<Value> {
         self.args.get(idx).cloned()
     }
@@ -1,22 +1,69 @@
 use std::cell::RefCell;
+#[cfg(feature = "bc-coverage")]
+use std::io;
 use std::rc::Rc;
-use std::time::Instant;
+#[cfg(feature = "bc-coverage")]
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use som_core::bytecode::Bytecode;
+#[cfg(feature = "bc-coverage")]
+use som_core::bytecode::{NAMES, NB_BYTECODES};
 
 use crate::block::Block;
-use crate::compiler::Literal;
+use crate::compiler::{nb_params, Literal};
 use crate::frame::{Frame, FrameKind};
 use crate::method::MethodKind;
 use crate::universe::Universe;
 use crate::value::Value;
 use crate::SOMRef;
 
+/// The number of times each bytecode has been dispatched, indexed by opcode.
+#[cfg(feature = "bc-coverage")]
+pub static BYTECODE_COUNTS: [AtomicU64; NB_BYTECODES] = {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const ZERO: AtomicU64 = AtomicU64::new(0);
+    [ZERO; NB_BYTECODES]
+};
+
+/// Write the dispatch counts of all bytecodes, one `NAME count` per line.
+#[cfg(feature = "bc-coverage")]
+pub fn dump_bytecode_counts(mut output: impl io::Write) -> io::Result<()> {
+    for (name, count) in NAMES.iter().zip(BYTECODE_COUNTS.iter()) {
+        writeln!(output, "{} {}", name, count.load(Ordering::Relaxed))?;
+    }
+    Ok(())
+}
+
+/// Report the dispatch counts of all bytecodes on stderr, unless diagnostics are suppressed.
+#[cfg(feature = "bc-coverage")]
+pub fn report_bytecode_counts() {
+    if crate::diagnostics_enabled() {
+        if let Err(err) = dump_bytecode_counts(io::stderr()) {
+            eprintln!("warning: could not report the bytecode counts: {}", err);
+        }
+    }
+}
+
+/// A time budget given to a block through `Block>>#valueWithTimeout:`.
+pub struct Timeout {
+    /// The frame of the timed block.
+    pub frame: SOMRef<Frame>,
+    /// The position of that frame within the interpreter's stack frames.
+    pub depth: usize,
+    /// The time budget given to the block.
+    pub budget: Duration,
+    /// When the time budget runs out.
+    pub deadline: Instant,
+}
+
 pub struct Interpreter {
     /// The interpreter's stack frames.
     pub frames: Vec<SOMRef<Frame>>,
     /// The time record of the interpreter's creation.
     pub start_time: Instant,
+    /// The time budgets of the blocks currently running through `Block>>#valueWithTimeout:`.
+    timeouts: Vec<Timeout>,
 }
 
 impl Interpreter {
@@ -24,6 +71,7 @@ impl Interpreter {
         Self {
             frames: vec![],
             start_time: Instant::now(),
+            timeouts: vec![],
         }
     }
 
@@ -41,6 +89,44 @@ impl Interpreter {
         self.frames.last()
     }
 
+    /// Give a time budget to the block running in the current frame.
+    pub fn push_timeout(&mut self, budget: Duration) {
+        self.forget_finished_timeouts();
+        let frame = self.current_frame().expect("no current frame").clone();
+        self.timeouts.push(Timeout {
+            frame,
+            depth: self.frames.len() - 1,
+            budget,
+            deadline: Instant::now() + budget,
+        });
+    }
+
+    /// Remove and return the outermost running block whose time budget has run out (along with those nested within it).
+    ///
+    /// This is checked at every loop iteration (`Block>>#restart`), and is cheap when no block is timed.
+    pub fn take_expired_timeout(&mut self) -> Option<Timeout> {
+        if self.timeouts.is_empty() {
+            return None;
+        }
+        self.forget_finished_timeouts();
+        let now = Instant::now();
+        let idx = self
+            .timeouts
+            .iter()
+            .position(|timeout| now >= timeout.deadline)?;
+        self.timeouts.drain(idx..).next()
+    }
+
+    /// Forget about the timed blocks which have already returned.
+    fn forget_finished_timeouts(&mut self) {
+        let frames = &self.frames;
+        self.timeouts.retain(|timeout| {
+            frames
+                .get(timeout.depth)
+                .is_some_and(|frame| Rc::ptr_eq(frame, &timeout.frame))
+        });
+    }
+
     pub fn run(&mut self, universe: &mut Universe) -> Option<Value> {
         loop {
             let frame = match self.current_frame() {
@@ -59,10 +145,28 @@ impl Interpreter {
                 }
             };
 
+            #[cfg(feature = "bc-coverage")]
+            BYTECODE_COUNTS[bytecode.opcode() as usize].fetch_add(1, Ordering::Relaxed);
+
+            #[cfg(feature = "stack-maps")]
+            {
+                let frame = frame.borrow();
+                if let Some(expected) = frame.expected_stack_height() {
+                    debug_assert_eq!(
+                        frame.stack.len(),
+                        expected,
+                        "stack height mismatch before bytecode {} ({})",
+                        frame.bytecode_idx,
+                        bytecode,
+                    );
+                }
+            }
+
             frame.borrow_mut().bytecode_idx += 1;
 
             match bytecode {
                 Bytecode::Halt => {
+                    // The frames are left untouched, so that a later call to `run` can resume execution.
                     return Some(Value::Nil);
                 }
                 Bytecode::Dup => {
@@ -134,9 +238,33 @@ impl Interpreter {
                         universe.unknown_global(self, self_value, symbol).unwrap();
                     }
                 }
+                Bytecode::PushGlobalCached(idx) => {
+                    let cached = frame.borrow().lookup_cached_global(idx as usize);
+                    if let Some(value) = cached {
+                        frame.borrow_mut().stack.push(value);
+                    } else {
+                        let literal = frame.borrow().lookup_constant(idx as usize).unwrap();
+                        let symbol = match literal {
+                            Literal::Symbol(sym) => sym,
+                            _ => return None,
+                        };
+                        if let Some(value) = universe.lookup_global(symbol) {
+                            frame.borrow().cache_global(idx as usize, value.clone());
+                            frame.borrow_mut().stack.push(value);
+                        } else {
+                            let self_value = frame.borrow().get_self();
+                            universe.unknown_global(self, self_value, symbol).unwrap();
+                        }
+                    }
+                }
                 Bytecode::Pop => {
                     frame.borrow_mut().stack.pop();
                 }
+                Bytecode::Pop2 => {
+                    let mut frame = frame.borrow_mut();
+                    frame.stack.pop();
+                    frame.stack.pop();
+                }
                 Bytecode::PopLocal(up_idx, idx) => {
                     let value = frame.borrow_mut().stack.pop().unwrap();
                     let mut from = frame.clone();
@@ -182,6 +310,12 @@ impl Interpreter {
                         self_value.assign_local(idx as usize, value).unwrap();
                     }
                 }
+                Bytecode::NilLocal(idx) => {
+                    frame
+                        .borrow_mut()
+                        .assign_local(idx as usize, Value::Nil)
+                        .unwrap();
+                }
                 Bytecode::Send(idx) => {
                     let literal = frame.borrow().lookup_constant(idx as usize).unwrap();
                     let symbol = match literal {
@@ -215,6 +349,8 @@ impl Interpreter {
                                 args.reverse();
 
                                 let holder = method.holder.upgrade().unwrap();
+                                #[cfg(feature = "coverage")]
+                                universe.record_invocation(&holder, symbol);
                                 self.push_frame(FrameKind::Method {
                                     self_value,
                                     method,
@@ -242,10 +378,15 @@ impl Interpreter {
 
                         args.reverse();
 
-                        universe.does_not_understand(self, self_value, symbol, args)
-                            .expect(
-                                "A message cannot be handled and `doesNotUnderstand:arguments:` is not defined on receiver"
+                        if universe
+                            .does_not_understand(self, self_value, symbol, args)
+                            .is_none()
+                        {
+                            panic!(
+                                "could not find method '#{}', and its receiver defines neither 'doesNotUnderstand:arguments:' nor 'error:'",
+                                universe.lookup_symbol(symbol),
                             );
+                        }
                     }
                 }
                 Bytecode::SuperSend(idx) => {
@@ -283,6 +424,8 @@ impl Interpreter {
                                 args.reverse();
 
                                 let holder = method.holder.upgrade().unwrap();
+                                #[cfg(feature = "coverage")]
+                                universe.record_invocation(&holder, symbol);
                                 self.push_frame(FrameKind::Method {
                                     self_value,
                                     method,
@@ -310,10 +453,15 @@ impl Interpreter {
 
                         args.reverse();
 
-                        universe.does_not_understand(self, self_value, symbol, args)
-                            .expect(
-                                "A message cannot be handled and `doesNotUnderstand:arguments:` is not defined on receiver"
+                        if universe
+                            .does_not_understand(self, self_value, symbol, args)
+                            .is_none()
+                        {
+                            panic!(
+                                "could not find method '#{}', and its receiver defines neither 'doesNotUnderstand:arguments:' nor 'error:'",
+                                universe.lookup_symbol(symbol),
                             );
+                        }
                     }
                 }
                 Bytecode::ReturnLocal => {
@@ -353,10 +501,24 @@ impl Interpreter {
                                 panic!("A method frame has escaped itself ??");
                             }
                         };
+                        let message = match method_frame.borrow().kind() {
+                            FrameKind::Method { holder, method, .. } => format!(
+                                "a block defined in '{}>>#{}' attempted a non-local return from that method, which has already returned",
+                                holder.borrow().name(),
+                                method.signature(),
+                            ),
+                            FrameKind::Block { .. } => unreachable!("`Frame::method_frame` returned a block frame"),
+                        };
                         // TODO: should we call `doesNotUnderstand:` here ?
-                        universe.escaped_block(self, instance, block).expect(
-                            "A block has escaped and `escapedBlock:` is not defined on receiver",
-                        );
+                        if universe
+                            .escaped_block(self, instance, block, message.clone())
+                            .is_none()
+                        {
+                            panic!(
+                                "{} (and `escapedBlock:` is not defined on the receiver)",
+                                message
+                            );
+                        }
                     }
                 }
             }
@@ -386,12 +548,5 @@ impl Interpreter {
             };
             Some(value)
         }
-
-        fn nb_params(signature: &str) -> usize {
-            match signature.chars().nth(0) {
-                Some(ch) if !ch.is_alphabetic() => 1,
-                _ => signature.chars().filter(|ch| *ch == ':').count(),
-            }
-        }
     }
 }
@@ -4,6 +4,7 @@
 
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Facilities for manipulating blocks.
 pub mod block;
@@ -34,3 +35,15 @@ pub mod value;
 pub type SOMRef<T> = Rc<RefCell<T>>;
 /// A weak reference to an object.
 pub type SOMWeakRef<T> = Weak<RefCell<T>>;
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Set whether to suppress all of the interpreter's own diagnostics (its warnings and reports on stderr).
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the interpreter's own diagnostics should be printed, which every one of them checks.
+pub fn diagnostics_enabled() -> bool {
+    !QUIET.load(Ordering::Relaxed)
+}
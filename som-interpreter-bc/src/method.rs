@@ -1,4 +1,6 @@
+use std::cell::OnceCell;
 use std::fmt;
+use std::io;
 use std::rc::Rc;
 
 use som_core::bytecode::Bytecode;
@@ -6,6 +8,7 @@ use som_core::bytecode::Bytecode;
 use crate::class::Class;
 use crate::compiler::Literal;
 use crate::frame::FrameKind;
+use crate::interner::Interner;
 use crate::interpreter::Interpreter;
 use crate::primitives;
 use crate::primitives::PrimitiveFn;
@@ -18,6 +21,11 @@ pub struct MethodEnv {
     pub locals: Vec<Value>,
     pub literals: Vec<Literal>,
     pub body: Vec<Bytecode>,
+    /// The resolved values of the globals pushed by `PushGlobalCached`, indexed like the literals.
+    pub global_cache: Rc<Vec<OnceCell<Value>>>,
+    /// The operand stack height expected before each bytecode, indexed like the body.
+    #[cfg(feature = "stack-maps")]
+    pub stack_map: Vec<usize>,
 }
 
 /// The kind of a class method.
@@ -63,6 +71,12 @@ impl MethodKind {
         //     primitive.is_some()
         // );
         primitive.map(MethodKind::Primitive).unwrap_or_else(|| {
+            if primitives::warn_missing_primitives() {
+                eprintln!(
+                    "warning: primitive '{}>>#{}' is declared but not implemented",
+                    class_name, signature
+                );
+            }
             MethodKind::NotImplemented(format!("{}>>#{}", class_name, signature))
         })
         // .unwrap_or_else(|| panic!("unimplemented primitive: '{}>>#{}'", class_name, signature))
@@ -108,6 +122,40 @@ impl Method {
         self.kind.is_primitive()
     }
 
+    /// Write this method's literal table, one `index kind value` per line (nothing for primitives).
+    ///
+    /// Nested blocks are not expanded, and array literals are shown as the indices of their elements.
+    pub fn dump_literals(&self, interner: &Interner, mut output: impl io::Write) -> io::Result<()> {
+        let env = match &self.kind {
+            MethodKind::Defined(env) => env,
+            MethodKind::Primitive(_) | MethodKind::NotImplemented(_) => return Ok(()),
+        };
+        for (idx, literal) in env.literals.iter().enumerate() {
+            let (kind, value) = match literal {
+                Literal::Symbol(value) => ("Symbol", format!("#{}", interner.lookup(*value))),
+                Literal::String(value) => ("String", format!("{:?}", value)),
+                Literal::Double(value) => ("Double", value.to_string()),
+                Literal::Integer(value) => ("Integer", value.to_string()),
+                Literal::BigInteger(value) => ("Integer", value.to_string()),
+                Literal::Array(indices) => ("Array", format!("{:?}", indices)),
+                Literal::Block(_) => ("Block", String::from("<block>")),
+            };
+            writeln!(output, "    {:<4} {:<8} {}", idx, kind, value)?;
+        }
+        Ok(())
+    }
+
+    /// Count the filled entries of this method's global cache (including those of its nested blocks),
+    /// out of the entries used by its `PushGlobalCached` bytecodes, as `(filled, total)`.
+    pub fn global_cache_usage(&self) -> (usize, usize) {
+        match &self.kind {
+            MethodKind::Defined(env) => {
+                global_cache_usage(&env.body, &env.literals, &env.global_cache)
+            }
+            MethodKind::Primitive(_) | MethodKind::NotImplemented(_) => (0, 0),
+        }
+    }
+
     pub fn invoke(
         self: Rc<Self>,
         interpreter: &mut Interpreter,
@@ -187,7 +235,11 @@ impl fmt::Display for Method {
                         Bytecode::PushGlobal(idx) => {
                             write!(f, "index: {}", idx)?;
                         }
+                        Bytecode::PushGlobalCached(idx) => {
+                            write!(f, "index: {}", idx)?;
+                        }
                         Bytecode::Pop => {}
+                        Bytecode::Pop2 => {}
                         Bytecode::PopLocal(up_idx, idx) => {
                             write!(f, "local: {}, context: {}", idx, up_idx)?;
                         }
@@ -197,6 +249,9 @@ impl fmt::Display for Method {
                         Bytecode::PopField(idx) => {
                             write!(f, "index: {}", idx)?;
                         }
+                        Bytecode::NilLocal(idx) => {
+                            write!(f, "local: {}", idx)?;
+                        }
                         Bytecode::Send(idx) => {
                             write!(f, "index: {}", idx)?;
                         }
@@ -214,3 +269,47 @@ impl fmt::Display for Method {
         }
     }
 }
+
+/// Count the filled entries of a global cache (recursing into nested blocks), as `(filled, total)`.
+fn global_cache_usage(
+    body: &[Bytecode],
+    literals: &[Literal],
+    global_cache: &[OnceCell<Value>],
+) -> (usize, usize) {
+    let mut indices: Vec<usize> = body
+        .iter()
+        .filter_map(|bytecode| match bytecode {
+            Bytecode::PushGlobalCached(idx) => Some(*idx as usize),
+            _ => None,
+        })
+        .collect();
+    indices.sort_unstable();
+    indices.dedup();
+
+    let filled = indices
+        .iter()
+        .filter(|idx| {
+            global_cache
+                .get(**idx)
+                .is_some_and(|slot| slot.get().is_some())
+        })
+        .count();
+    let total = indices.len();
+
+    literals
+        .iter()
+        .filter_map(|literal| match literal {
+            Literal::Block(block) => Some(global_cache_usage(
+                &block.body,
+                &block.literals,
+                &block.global_cache,
+            )),
+            _ => None,
+        })
+        .fold(
+            (filled, total),
+            |(filled, total), (block_filled, block_total)| {
+                (filled + block_filled, total + block_total)
+            },
+        )
+}
@@ -1,7 +1,12 @@
 use std::cell::RefCell;
 use std::convert::TryFrom;
+use std::ops::Range;
 use std::rc::Rc;
 
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
 use crate::interpreter::Interpreter;
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
@@ -15,12 +20,12 @@ fn at(interpreter: &mut Interpreter, _: &mut Universe) {
 
     expect_args!(SIGNATURE, frame, [
         Value::Array(values) => values,
-        Value::Integer(index) => index,
+        index => index,
     ]);
 
-    let index = match usize::try_from(index - 1) {
-        Ok(index) => index,
-        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    let index = match index.as_index().and_then(|index| index.checked_sub(1)) {
+        Some(index) => index,
+        None => panic!("'{}': invalid index", SIGNATURE),
     };
     let value = values.borrow().get(index).cloned().unwrap_or(Value::Nil);
     frame.borrow_mut().stack.push(value)
@@ -33,13 +38,13 @@ fn at_put(interpreter: &mut Interpreter, _: &mut Universe) {
 
     expect_args!(SIGNATURE, frame, [
         Value::Array(values) => values,
-        Value::Integer(index) => index,
+        index => index,
         value => value,
     ]);
 
-    let index = match usize::try_from(index - 1) {
-        Ok(index) => index,
-        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    let index = match index.as_index().and_then(|index| index.checked_sub(1)) {
+        Some(index) => index,
+        None => panic!("'{}': invalid index", SIGNATURE),
     };
     if let Some(location) = values.borrow_mut().get_mut(index) {
         *location = value;
@@ -85,6 +90,187 @@ fn new(interpreter: &mut Interpreter, _: &mut Universe) {
     }
 }
 
+fn first(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#first:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+        Value::Integer(count) => count,
+    ]);
+
+    let values = values.borrow();
+    let count = usize::try_from(count).unwrap_or(0).min(values.len());
+    let prefix = values[..count].to_vec();
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Array(Rc::new(RefCell::new(prefix))));
+}
+
+fn last(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#last:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+        Value::Integer(count) => count,
+    ]);
+
+    let values = values.borrow();
+    let count = usize::try_from(count).unwrap_or(0).min(values.len());
+    let suffix = values[values.len() - count..].to_vec();
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Array(Rc::new(RefCell::new(suffix))));
+}
+
+fn occurrences_of(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#occurrencesOf:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+        value => value,
+    ]);
+
+    let count = values.borrow().iter().filter(|it| **it == value).count();
+    match i64::try_from(count) {
+        Ok(count) => frame.borrow_mut().stack.push(Value::Integer(count)),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    }
+}
+
+fn remove_all(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#removeAll:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+        value => value,
+    ]);
+
+    let remaining: Vec<_> = values
+        .borrow()
+        .iter()
+        .filter(|it| **it != value)
+        .cloned()
+        .collect();
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Array(Rc::new(RefCell::new(remaining))));
+}
+
+fn replace_from_to_with(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#replaceFrom:to:with:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+        Value::Integer(start) => start,
+        Value::Integer(end) => end,
+        Value::Array(source) => source,
+    ]);
+
+    let spliced = splice_range(&values.borrow(), start, end, &source.borrow());
+    match spliced {
+        Some((range, replacement)) => {
+            values.borrow_mut()[range].clone_from_slice(&replacement);
+            frame.borrow_mut().stack.push(Value::Array(values));
+        }
+        None => frame.borrow_mut().stack.push(Value::Nil),
+    }
+}
+
+/// Resolve the inclusive (1-based) range of `values` to replace with the first elements of `source`.
+///
+/// Returns `None` if the range is out of bounds or if `source` is too short for it.
+fn splice_range(
+    values: &[Value],
+    start: i64,
+    end: i64,
+    source: &[Value],
+) -> Option<(Range<usize>, Vec<Value>)> {
+    let start = usize::try_from(start).ok()?.checked_sub(1)?;
+    let end = usize::try_from(end).ok()?;
+    if start > end || end > values.len() {
+        return None;
+    }
+    // The source is copied, since it may well be the receiver itself.
+    let replacement = source.get(..end - start)?.to_vec();
+    Some((start..end, replacement))
+}
+
+fn shuffle(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#shuffle:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+        Value::Integer(seed) => seed,
+    ]);
+
+    let shuffled = shuffled_with_seed(&values.borrow(), seed);
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Array(Rc::new(RefCell::new(shuffled))));
+}
+
+/// Copy the given values in a (Fisher-Yates) shuffled order, which only depends on the given seed.
+fn shuffled_with_seed(values: &[Value], seed: i64) -> Vec<Value> {
+    let mut shuffled = values.to_vec();
+    let mut rng = StdRng::seed_from_u64(seed as u64);
+    shuffled.shuffle(&mut rng);
+    shuffled
+}
+
+fn remove_duplicates(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Array>>#removeDuplicates";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Array(values) => values,
+    ]);
+
+    let unique = without_duplicates(&values.borrow());
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Array(Rc::new(RefCell::new(unique))));
+}
+
+/// Copy the given values, keeping only the first occurrence of each of them (in their original order).
+fn without_duplicates(values: &[Value]) -> Vec<Value> {
+    let mut unique: Vec<Value> = Vec::with_capacity(values.len());
+    for value in values {
+        if !unique.contains(value) {
+            unique.push(value.clone());
+        }
+    }
+    unique
+}
+
+/// The primitives this interpreter provides for the **Array** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "first:",
+    "last:",
+    "occurrencesOf:",
+    "removeAll:",
+    "replaceFrom:to:with:",
+    "shuffle:",
+    "removeDuplicates",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -92,6 +278,13 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "at:put:" => Some(self::at_put),
         "length" => Some(self::length),
         "new:" => Some(self::new),
+        "first:" => Some(self::first),
+        "last:" => Some(self::last),
+        "occurrencesOf:" => Some(self::occurrences_of),
+        "removeAll:" => Some(self::remove_all),
+        "replaceFrom:to:with:" => Some(self::replace_from_to_with),
+        "shuffle:" => Some(self::shuffle),
+        "removeDuplicates" => Some(self::remove_duplicates),
         _ => None,
     }
 }
@@ -1,3 +1,6 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
 use crate::frame::FrameKind;
 use crate::interpreter::Interpreter;
 use crate::primitives::PrimitiveFn;
@@ -25,7 +28,7 @@ pub mod block1 {
         interpreter.push_frame(kind);
     }
 
-    fn restart(interpreter: &mut Interpreter, _: &mut Universe) {
+    fn restart(interpreter: &mut Interpreter, universe: &mut Universe) {
         const SIGNATURE: &str = "Block>>#restart";
 
         let frame = interpreter.current_frame().expect("no current frame");
@@ -33,13 +36,57 @@ pub mod block1 {
         expect_args!(SIGNATURE, frame, [Value::Block(_)]);
 
         frame.borrow_mut().bytecode_idx = 0;
+
+        // Restarting is how loops iterate, so this is where timed blocks get interrupted.
+        if let Some(timeout) = interpreter.take_expired_timeout() {
+            let block = match timeout.frame.borrow().kind() {
+                FrameKind::Block { block } => Value::Block(block.clone()),
+                FrameKind::Method { .. } => unreachable!("a method frame was given a timeout"),
+            };
+            interpreter.frames.truncate(timeout.depth);
+            let message = format!(
+                "block exceeded its time budget of {} ms",
+                timeout.budget.as_millis()
+            );
+            if universe
+                .error(interpreter, block, message.clone())
+                .is_none()
+            {
+                panic!("{} (and `error:` is not defined on the block)", message);
+            }
+        }
+    }
+
+    fn value_with_timeout(interpreter: &mut Interpreter, _: &mut Universe) {
+        const SIGNATURE: &str = "Block>>#valueWithTimeout:";
+
+        let frame = interpreter.current_frame().expect("no current frame");
+
+        expect_args!(SIGNATURE, frame, [
+            Value::Block(block) => block,
+            Value::Integer(millis) => millis,
+        ]);
+
+        if block.nb_parameters() != 0 {
+            panic!("'{}': the block should not expect any argument", SIGNATURE);
+        }
+
+        let budget = Duration::from_millis(u64::try_from(millis).unwrap_or(0));
+        interpreter.push_frame(FrameKind::Block {
+            block: block.clone(),
+        });
+        interpreter.push_timeout(budget);
     }
 
+    /// The primitives this interpreter provides for the **Block** class, beyond those declared by the core library.
+    pub static EXTENSIONS: &[&str] = &["valueWithTimeout:"];
+
     /// Search for a primitive matching the given signature.
     pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         match signature.as_ref() {
             "value" => Some(self::value),
             "restart" => Some(self::restart),
+            "valueWithTimeout:" => Some(self::value_with_timeout),
             _ => None,
         }
     }
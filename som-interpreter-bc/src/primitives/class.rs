@@ -96,6 +96,64 @@ fn fields(interpreter: &mut Interpreter, _: &mut Universe) {
         ))));
 }
 
+fn all_inst_var_names(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Class>>#allInstVarNames";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Class(class) => class,
+    ]);
+
+    // The class' locals already include the inherited ones, in field-layout order.
+    let names = class
+        .borrow()
+        .locals
+        .keys()
+        .copied()
+        .map(Value::Symbol)
+        .collect();
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Array(Rc::new(RefCell::new(names))));
+}
+
+fn comment(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Class>>#comment";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Class(class) => class,
+    ]);
+
+    let comment = class.borrow().comment.clone().unwrap_or_default();
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(comment)));
+}
+
+fn includes_behavior(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Class>>#includesBehavior:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Class(class) => class,
+        Value::Class(other) => other,
+    ]);
+
+    let includes = std::iter::successors(Some(class), |class| class.borrow().super_class())
+        .any(|class| Rc::ptr_eq(&class, &other));
+    frame.borrow_mut().stack.push(Value::Boolean(includes));
+}
+
+/// The primitives this interpreter provides for the **Class** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &["allInstVarNames", "comment", "includesBehavior:"];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -104,6 +162,9 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "fields" => Some(self::fields),
         "methods" => Some(self::methods),
         "superclass" => Some(self::superclass),
+        "allInstVarNames" => Some(self::all_inst_var_names),
+        "comment" => Some(self::comment),
+        "includesBehavior:" => Some(self::includes_behavior),
         _ => None,
     }
 }
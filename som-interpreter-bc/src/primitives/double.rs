@@ -1,5 +1,8 @@
+use std::convert::TryFrom;
 use std::rc::Rc;
 
+use num_traits::ToPrimitive;
+
 use crate::interpreter::Interpreter;
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
@@ -52,6 +55,26 @@ fn as_string(interpreter: &mut Interpreter, _: &mut Universe) {
         .push(Value::String(Rc::new(value.to_string())));
 }
 
+fn as_string_with_decimals(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#asStringWithDecimals:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Integer(decimals) => decimals,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(format_with_decimals(
+            value, decimals,
+        ))));
+}
+
 fn as_integer(interpreter: &mut Interpreter, _: &mut Universe) {
     const SIGNATURE: &str = "Double>>#asInteger";
 
@@ -67,6 +90,34 @@ fn as_integer(interpreter: &mut Interpreter, _: &mut Universe) {
         .push(Value::Integer(value.trunc() as i64));
 }
 
+fn abs(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#abs";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    frame.borrow_mut().stack.push(Value::Double(value.abs()));
+}
+
+fn negated(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#negated";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    frame.borrow_mut().stack.push(Value::Double(-value));
+}
+
 fn sqrt(interpreter: &mut Interpreter, _: &mut Universe) {
     const SIGNATURE: &str = "Double>>#sqrt";
 
@@ -123,18 +174,83 @@ fn sin(interpreter: &mut Interpreter, _: &mut Universe) {
     frame.borrow_mut().stack.push(Value::Double(value.sin()));
 }
 
+fn arc_sin(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#arcSin";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    frame.borrow_mut().stack.push(Value::Double(value.asin()));
+}
+
+fn arc_cos(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#arcCos";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    frame.borrow_mut().stack.push(Value::Double(value.acos()));
+}
+
+fn arc_tan(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#arcTan";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = promote!(SIGNATURE, value);
+
+    frame.borrow_mut().stack.push(Value::Double(value.atan()));
+}
+
+fn arc_tan2(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#arcTan:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        y => y,
+        x => x,
+    ]);
+
+    let y = promote!(SIGNATURE, y);
+    let x = promote!(SIGNATURE, x);
+
+    frame.borrow_mut().stack.push(Value::Double(y.atan2(x)));
+}
+
 fn eq(interpreter: &mut Interpreter, _: &mut Universe) {
     const SIGNATURE: &str = "Double>>#=";
 
     let frame = interpreter.current_frame().expect("no current frame");
 
     expect_args!(SIGNATURE, frame, [
-        // Value::Double(a) => a,
-        // Value::Double(b) => b,
         a => a,
         b => b,
     ]);
 
+    // Anything that is not a number is never equal, and neither is NaN (not even to itself).
+    let a = promote!(SIGNATURE, a);
+    let b = match b {
+        Value::Integer(b) => b as f64,
+        Value::Double(b) => b,
+        Value::BigInteger(b) => b.to_f64().unwrap_or(f64::NAN),
+        _ => f64::NAN,
+    };
+
     frame.borrow_mut().stack.push(Value::Boolean(a == b));
 }
 
@@ -246,6 +362,82 @@ fn positive_infinity(interpreter: &mut Interpreter, _: &mut Universe) {
     frame.borrow_mut().stack.push(Value::Double(f64::INFINITY));
 }
 
+fn is_nan(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#isNaN";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Double(value) => value,
+    ]);
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Boolean(value.is_nan()));
+}
+
+fn is_infinite(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#isInfinite";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Double(value) => value,
+    ]);
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Boolean(value.is_infinite()));
+}
+
+fn is_finite(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Double>>#isFinite";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Double(value) => value,
+    ]);
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Boolean(value.is_finite()));
+}
+
+/// The most decimals a double is formatted with, which is enough to write any `f64` exactly
+/// (the smallest subnormal, `2^-1074`, has that many).
+const MAX_DECIMALS: usize = 1074;
+
+/// Format a double with a fixed number of decimals (a negative count being treated as zero, and a larger one than
+/// `MAX_DECIMALS` as that maximum).
+fn format_with_decimals(value: f64, decimals: i64) -> String {
+    if value.is_nan() {
+        String::from("nan")
+    } else if value.is_infinite() {
+        String::from(if value > 0.0 { "inf" } else { "-inf" })
+    } else {
+        let decimals = usize::try_from(decimals).unwrap_or(0);
+        format!("{:.*}", decimals.min(MAX_DECIMALS), value)
+    }
+}
+
+/// The primitives this interpreter provides for the **Double** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "isNaN",
+    "isInfinite",
+    "isFinite",
+    "asStringWithDecimals:",
+    "abs",
+    "negated",
+    "arcSin",
+    "arcCos",
+    "arcTan",
+    "arcTan:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -257,13 +449,23 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "=" => Some(self::eq),
         "<" => Some(self::lt),
         "sqrt" => Some(self::sqrt),
+        "abs" => Some(self::abs),
+        "negated" => Some(self::negated),
         "round" => Some(self::round),
         "cos" => Some(self::cos),
         "sin" => Some(self::sin),
+        "arcSin" => Some(self::arc_sin),
+        "arcCos" => Some(self::arc_cos),
+        "arcTan" => Some(self::arc_tan),
+        "arcTan:" => Some(self::arc_tan2),
         "fromString:" => Some(self::from_string),
         "asString" => Some(self::as_string),
+        "asStringWithDecimals:" => Some(self::as_string_with_decimals),
         "asInteger" => Some(self::as_integer),
         "PositiveInfinity" => Some(self::positive_infinity),
+        "isNaN" => Some(self::is_nan),
+        "isInfinite" => Some(self::is_infinite),
+        "isFinite" => Some(self::is_finite),
         _ => None,
     }
 }
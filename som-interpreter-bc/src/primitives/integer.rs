@@ -1,11 +1,14 @@
+use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::rc::Rc;
 
 use num_bigint::{BigInt, Sign};
-use num_traits::ToPrimitive;
+use num_traits::{Signed, ToPrimitive};
 use rand::distributions::Uniform;
 use rand::Rng;
 
 use crate::interpreter::Interpreter;
+use crate::primitives::string::pad_to_width;
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
 use crate::value::Value;
@@ -55,7 +58,7 @@ fn from_string(interpreter: &mut Interpreter, universe: &mut Universe) {
     }
 }
 
-fn as_string(interpreter: &mut Interpreter, _: &mut Universe) {
+fn as_string(interpreter: &mut Interpreter, universe: &mut Universe) {
     const SIGNATURE: &str = "Integer>>#asString";
 
     let frame = interpreter.current_frame().expect("no current frame");
@@ -65,17 +68,94 @@ fn as_string(interpreter: &mut Interpreter, _: &mut Universe) {
     ]);
 
     let value = match value {
-        Value::Integer(value) => value.to_string(),
-        Value::BigInteger(value) => value.to_string(),
+        Value::Integer(value) => universe.integer_string(value),
+        Value::BigInteger(value) => Rc::new(value.to_string()),
         _ => panic!("'{}': wrong types", SIGNATURE),
     };
 
     {
-        frame.borrow_mut().stack.push(Value::String(Rc::new(value)));
+        frame.borrow_mut().stack.push(Value::String(value));
         return;
     }
 }
 
+fn print_string_radix(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#printString:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Integer(radix) => radix,
+    ]);
+
+    let value = match value {
+        Value::Integer(value) => BigInt::from(value),
+        Value::BigInteger(value) => value,
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    match to_radix_string(&value, radix) {
+        Some(string) => frame
+            .borrow_mut()
+            .stack
+            .push(Value::String(Rc::new(string))),
+        None => panic!("'{}': invalid radix", SIGNATURE),
+    }
+}
+
+fn print_string_right_justified(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#printStringRightJustified:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Integer(width) => width,
+    ]);
+
+    let value = match value {
+        Value::Integer(value) => value.to_string(),
+        Value::BigInteger(value) => value.to_string(),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    let justified = match pad_to_width(&value, width, ' ', true) {
+        Ok(padded) => padded.unwrap_or(value),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    };
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(justified)));
+}
+
+fn print_string_left_justified(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#printStringLeftJustified:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Integer(width) => width,
+    ]);
+
+    let value = match value {
+        Value::Integer(value) => value.to_string(),
+        Value::BigInteger(value) => value.to_string(),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    let justified = match pad_to_width(&value, width, ' ', false) {
+        Ok(padded) => padded.unwrap_or(value),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    };
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(justified)));
+}
+
 fn at_random(interpreter: &mut Interpreter, _: &mut Universe) {
     const SIGNATURE: &str = "Integer>>#atRandom";
 
@@ -372,6 +452,69 @@ fn remainder(interpreter: &mut Interpreter, _: &mut Universe) {
     }
 }
 
+fn as_big_integer(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#asBigInteger";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    match value {
+        Value::Integer(value) => frame
+            .borrow_mut()
+            .stack
+            .push(Value::BigInteger(BigInt::from(value))),
+        Value::BigInteger(_) => frame.borrow_mut().stack.push(value),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
+fn abs(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#abs";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        a => a,
+    ]);
+
+    match a {
+        Value::Integer(a) => match a.checked_abs() {
+            Some(value) => frame.borrow_mut().stack.push(Value::Integer(value)),
+            None => frame
+                .borrow_mut()
+                .stack
+                .push(Value::BigInteger(BigInt::from(a).abs())),
+        },
+        Value::BigInteger(a) => demote!(frame, a.abs()),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
+fn negated(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#negated";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        a => a,
+    ]);
+
+    match a {
+        Value::Integer(a) => match a.checked_neg() {
+            Some(value) => frame.borrow_mut().stack.push(Value::Integer(value)),
+            None => frame
+                .borrow_mut()
+                .stack
+                .push(Value::BigInteger(-BigInt::from(a))),
+        },
+        Value::BigInteger(a) => demote!(frame, -a),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
 fn sqrt(interpreter: &mut Interpreter, _: &mut Universe) {
     const SIGNATURE: &str = "Integer>>#sqrt";
 
@@ -522,6 +665,13 @@ fn eq(interpreter: &mut Interpreter, _: &mut Universe) {
             frame.borrow_mut().stack.push(Value::Boolean(a == b));
             return;
         }
+        (Value::Integer(a), Value::BigInteger(b)) | (Value::BigInteger(b), Value::Integer(a)) => {
+            frame
+                .borrow_mut()
+                .stack
+                .push(Value::Boolean(BigInt::from(a) == b));
+            return;
+        }
         (Value::Double(a), Value::Double(b)) => {
             frame.borrow_mut().stack.push(Value::Boolean(a == b));
             return;
@@ -586,11 +736,74 @@ fn shift_right(interpreter: &mut Interpreter, _: &mut Universe) {
     }
 }
 
+fn to(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Integer>>#to:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        start => start,
+        end => end,
+    ]);
+
+    match materialize_range(&start, &end) {
+        Some(value) => frame.borrow_mut().stack.push(value),
+        None => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
+/// The maximum number of elements that `Integer>>#to:` will materialize.
+const MAX_RANGE_LENGTH: i64 = 1 << 24;
+
+/// Materialize the range from `start` to `end` (inclusive), or `nil` if it is too large.
+fn materialize_range(start: &Value, end: &Value) -> Option<Value> {
+    let (start, end) = match (start, end) {
+        (Value::Integer(start), Value::Integer(end)) => (*start, *end),
+        (Value::Integer(_), Value::BigInteger(_))
+        | (Value::BigInteger(_), Value::Integer(_))
+        | (Value::BigInteger(_), Value::BigInteger(_)) => return Some(Value::Nil),
+        _ => return None,
+    };
+    let values = if end < start {
+        Vec::new()
+    } else if end.saturating_sub(start) >= MAX_RANGE_LENGTH {
+        return Some(Value::Nil);
+    } else {
+        (start..=end).map(Value::Integer).collect()
+    };
+    Some(Value::Array(Rc::new(RefCell::new(values))))
+}
+
+/// Render an integer in the given radix (between 2 and 36), with a leading `-` if it is negative.
+fn to_radix_string(value: &BigInt, radix: i64) -> Option<String> {
+    let radix = u32::try_from(radix)
+        .ok()
+        .filter(|radix| (2..=36).contains(radix))?;
+    Some(value.to_str_radix(radix))
+}
+
+/// The primitives this interpreter provides for the **Integer** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "to:",
+    "printString:",
+    "asString:",
+    "abs",
+    "asBigInteger",
+    "negated",
+    "printStringRightJustified:",
+    "printStringLeftJustified:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
         "fromString:" => Some(self::from_string),
         "asString" => Some(self::as_string),
+        "printString:" => Some(self::print_string_radix),
+        // `asString:` is an alias of `printString:`.
+        "asString:" => Some(self::print_string_radix),
+        "printStringRightJustified:" => Some(self::print_string_right_justified),
+        "printStringLeftJustified:" => Some(self::print_string_left_justified),
         "atRandom" => Some(self::at_random),
         "as32BitSignedValue" => Some(self::as_32bit_signed_value),
         "as32BitUnsignedValue" => Some(self::as_32bit_unsigned_value),
@@ -608,6 +821,10 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         ">>>" => Some(self::shift_right),
         "bitXor:" => Some(self::bitxor),
         "sqrt" => Some(self::sqrt),
+        "abs" => Some(self::abs),
+        "negated" => Some(self::negated),
+        "asBigInteger" => Some(self::as_big_integer),
+        "to:" => Some(self::to),
         _ => None,
     }
 }
@@ -1,12 +1,16 @@
+use std::cell::RefCell;
 use std::collections::hash_map::DefaultHasher;
 use std::convert::TryFrom;
 use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
+use crate::frame::{Frame, FrameKind};
+use crate::instance::Instance;
 use crate::interpreter::Interpreter;
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
 use crate::value::Value;
-use crate::{expect_args, reverse};
+use crate::{expect_args, reverse, SOMRef};
 
 fn class(interpreter: &mut Interpreter, universe: &mut Universe) {
     const SIGNATURE: &'static str = "Object>>#class";
@@ -50,6 +54,152 @@ fn hashcode(interpreter: &mut Interpreter, _: &mut Universe) {
     frame.borrow_mut().stack.push(Value::Integer(hash));
 }
 
+fn print_nl(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#printNl";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    println!("{}", value.to_string(universe));
+    frame.borrow_mut().stack.push(value);
+}
+
+fn is_nil(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#isNil";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Boolean(value.is_nil()));
+}
+
+fn assert(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#assert:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Boolean(condition) => condition,
+    ]);
+
+    if condition {
+        frame.borrow_mut().stack.push(value);
+    } else {
+        assertion_failed(
+            interpreter,
+            universe,
+            value,
+            String::from("assertion failed"),
+        );
+    }
+}
+
+fn assert_description(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#assert:description:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Boolean(condition) => condition,
+        Value::String(description) => description,
+    ]);
+
+    if condition {
+        frame.borrow_mut().stack.push(value);
+    } else {
+        let message = format!("assertion failed: {}", description);
+        assertion_failed(interpreter, universe, value, message);
+    }
+}
+
+/// Report a failed assertion through the receiver's `error:`.
+fn assertion_failed(
+    interpreter: &mut Interpreter,
+    universe: &mut Universe,
+    value: Value,
+    message: String,
+) {
+    if universe
+        .error(interpreter, value, message.clone())
+        .is_none()
+    {
+        panic!("{} (and `error:` is not defined on the receiver)", message);
+    }
+}
+
+fn this_context(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#thisContext";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [_]);
+
+    let context = reify_frames(universe, &interpreter.frames);
+    frame.borrow_mut().stack.push(context);
+}
+
+/// Build read-only **Context** instances for the given frames (outermost first), returning the innermost one.
+///
+/// Block frames report the signature of their enclosing method, and each bytecode index is the one of the next bytecode to run.
+fn reify_frames(universe: &mut Universe, frames: &[SOMRef<Frame>]) -> Value {
+    let class = universe.context_class();
+    frames.iter().fold(Value::Nil, |sender, frame| {
+        let signature = match Frame::method_frame(frame).borrow().kind() {
+            FrameKind::Method { method, .. } => universe.intern_symbol(method.signature()),
+            FrameKind::Block { .. } => unreachable!("`Frame::method_frame` returned a block frame"),
+        };
+        let bytecode_idx = frame.borrow().bytecode_idx;
+
+        let mut context = Instance::from_class(class.clone());
+        context.locals = vec![
+            Value::Symbol(signature),
+            Value::Integer(bytecode_idx as i64),
+            sender,
+        ];
+        Value::Instance(Rc::new(RefCell::new(context)))
+    })
+}
+
+fn not_nil(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#notNil";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::Boolean(!value.is_nil()));
+}
+
+fn responds_to(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#respondsTo:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        object => object,
+        Value::Symbol(sym) => sym,
+    ]);
+
+    let responds = object.lookup_method(universe, sym).is_some();
+    frame.borrow_mut().stack.push(Value::Boolean(responds));
+}
+
 fn eq(interpreter: &mut Interpreter, _: &mut Universe) {
     const SIGNATURE: &'static str = "Object>>#==";
 
@@ -60,7 +210,13 @@ fn eq(interpreter: &mut Interpreter, _: &mut Universe) {
         b => b,
     ]);
 
-    frame.borrow_mut().stack.push(Value::Boolean(a == b));
+    // Arrays compare their contents with `Value::eq`, so identity has to be checked separately.
+    let identical = match (&a, &b) {
+        (Value::Array(a), Value::Array(b)) => Rc::ptr_eq(a, b),
+        _ => a == b,
+    };
+
+    frame.borrow_mut().stack.push(Value::Boolean(identical));
 }
 
 fn perform(interpreter: &mut Interpreter, universe: &mut Universe) {
@@ -254,6 +410,39 @@ fn inst_var_at_put(interpreter: &mut Interpreter, _: &mut Universe) {
     frame.borrow_mut().stack.push(local);
 }
 
+fn as_string(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "Object>>#asString";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        object => object,
+    ]);
+
+    let class = object.class(universe);
+    let description = describe_instance(class.borrow().name());
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(description)));
+}
+
+/// Describes an instance of the named class, as in "a Point" or "an Object".
+fn describe_instance(class_name: &str) -> String {
+    let article = match class_name.chars().next() {
+        Some(ch) if "AEIOUaeiou".contains(ch) => "an",
+        _ => "a",
+    };
+    format!("{} {}", article, class_name)
+}
+
+/// The primitives this interpreter provides for the **Object** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &["printNl", "assert:", "assert:description:", "thisContext"];
+
+/// The primitives this interpreter provides for the **Object** class, in place of the core library's definitions.
+pub static OVERRIDES: &[&str] = &["asString", "respondsTo:", "isNil", "notNil"];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -266,6 +455,14 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "perform:withArguments:inSuperclass:" => Some(self::perform_with_arguments_in_super_class),
         "instVarAt:" => Some(self::inst_var_at),
         "instVarAt:put:" => Some(self::inst_var_at_put),
+        "printNl" => Some(self::print_nl),
+        "respondsTo:" => Some(self::responds_to),
+        "isNil" => Some(self::is_nil),
+        "notNil" => Some(self::not_nil),
+        "assert:" => Some(self::assert),
+        "assert:description:" => Some(self::assert_description),
+        "thisContext" => Some(self::this_context),
+        "asString" => Some(self::as_string),
         "==" => Some(self::eq),
         _ => None,
     }
@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::collections::hash_map::DefaultHasher;
 use std::convert::TryFrom;
 use std::hash::Hasher;
@@ -179,6 +180,82 @@ fn eq(interpreter: &mut Interpreter, _: &mut Universe) {
     frame.borrow_mut().stack.push(Value::Boolean(s1 == s2))
 }
 
+fn lt(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#<";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        s1 => s1,
+        s2 => s2,
+    ]);
+
+    match compare_strings(universe, &s1, &s2) {
+        Some(ordering) => frame
+            .borrow_mut()
+            .stack
+            .push(Value::Boolean(ordering == Ordering::Less)),
+        None => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
+fn lte(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#<=";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        s1 => s1,
+        s2 => s2,
+    ]);
+
+    match compare_strings(universe, &s1, &s2) {
+        Some(ordering) => frame
+            .borrow_mut()
+            .stack
+            .push(Value::Boolean(ordering != Ordering::Greater)),
+        None => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
+fn gt(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#>";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        s1 => s1,
+        s2 => s2,
+    ]);
+
+    match compare_strings(universe, &s1, &s2) {
+        Some(ordering) => frame
+            .borrow_mut()
+            .stack
+            .push(Value::Boolean(ordering == Ordering::Greater)),
+        None => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
+fn gte(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#>=";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        s1 => s1,
+        s2 => s2,
+    ]);
+
+    match compare_strings(universe, &s1, &s2) {
+        Some(ordering) => frame
+            .borrow_mut()
+            .stack
+            .push(Value::Boolean(ordering != Ordering::Less)),
+        None => panic!("'{}': wrong types", SIGNATURE),
+    }
+}
+
 fn prim_substring_from_to(interpreter: &mut Interpreter, universe: &mut Universe) {
     const SIGNATURE: &str = "String>>#primSubstringFrom:to:";
 
@@ -186,13 +263,15 @@ fn prim_substring_from_to(interpreter: &mut Interpreter, universe: &mut Universe
 
     expect_args!(SIGNATURE, frame, [
         value => value,
-        Value::Integer(from) => from,
-        Value::Integer(to) => to,
+        from => from,
+        to => to,
     ]);
 
-    let (value, from, to) = match (&value, usize::try_from(from - 1), usize::try_from(to)) {
-        (Value::String(ref value), Ok(from), Ok(to)) => (value.as_str(), from, to),
-        (Value::Symbol(sym), Ok(from), Ok(to)) => (universe.lookup_symbol(*sym), from, to),
+    let from = from.as_index().and_then(|from| from.checked_sub(1));
+    let to = to.as_index();
+    let (value, from, to) = match (&value, from, to) {
+        (Value::String(ref value), Some(from), Some(to)) => (value.as_str(), from, to),
+        (Value::Symbol(sym), Some(from), Some(to)) => (universe.lookup_symbol(*sym), from, to),
         (_, _, _) => panic!("'{}': wrong types", SIGNATURE),
     };
 
@@ -201,6 +280,469 @@ fn prim_substring_from_to(interpreter: &mut Interpreter, universe: &mut Universe
     frame.borrow_mut().stack.push(Value::String(string))
 }
 
+fn as_number(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#asNumber";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = match value {
+        Value::String(ref value) => value.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': invalid self type", SIGNATURE),
+    };
+
+    frame.borrow_mut().stack.push(parse_number(value))
+}
+
+fn reverse(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#reverse";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let value = match value {
+        Value::String(ref value) => value.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': invalid self type", SIGNATURE),
+    };
+
+    let reversed = value.chars().rev().collect();
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(reversed)))
+}
+
+fn print_string(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#printString";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+    ]);
+
+    let printed = match value {
+        Value::String(ref value) => {
+            format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+        }
+        Value::Symbol(_) => value.to_string(universe),
+        _ => panic!("'{}': invalid self type", SIGNATURE),
+    };
+
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(printed)))
+}
+
+fn occurrences_of(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#occurrencesOf:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        needle => needle,
+    ]);
+
+    let value = match value {
+        Value::String(ref value) => value.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+    let needle = match needle {
+        Value::String(ref needle) => needle.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    // Matches are non-overlapping, and an empty needle is considered to never occur.
+    let count = if needle.is_empty() {
+        0
+    } else {
+        value.matches(needle).count()
+    };
+
+    match i64::try_from(count) {
+        Ok(count) => frame.borrow_mut().stack.push(Value::Integer(count)),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    }
+}
+
+fn index_of(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#indexOf:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        needle => needle,
+    ]);
+
+    let value = match value {
+        Value::String(ref value) => value.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+    let needle = match needle {
+        Value::String(ref needle) => needle.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    match i64::try_from(index_of_substring(value, needle)) {
+        Ok(idx) => frame.borrow_mut().stack.push(Value::Integer(idx)),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    }
+}
+
+fn matches_regex(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#matchesRegex:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        pattern => pattern,
+    ]);
+
+    let value = match value {
+        Value::String(ref value) => value.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+    let pattern = match pattern {
+        Value::String(ref pattern) => pattern.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    let result = match parse_glob(pattern) {
+        Some(tokens) => Value::Boolean(glob_matches(&tokens, value)),
+        None => Value::Nil,
+    };
+    frame.borrow_mut().stack.push(result);
+}
+
+fn left_pad(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#leftPad:with:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Integer(width) => width,
+        Value::String(padding) => padding,
+    ]);
+
+    let padding = match single_char(padding.as_str()) {
+        Some(padding) => padding,
+        None => panic!("'{}': the padding must be a single character", SIGNATURE),
+    };
+    let padded = match value {
+        Value::String(ref string) => pad_to_width(string.as_str(), width, padding, true),
+        Value::Symbol(sym) => pad_to_width(universe.lookup_symbol(sym), width, padding, true),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    let result = match padded {
+        Ok(padded) => padded.map_or(value, |padded| Value::String(Rc::new(padded))),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    };
+    frame.borrow_mut().stack.push(result);
+}
+
+fn right_pad(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#rightPad:with:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        value => value,
+        Value::Integer(width) => width,
+        Value::String(padding) => padding,
+    ]);
+
+    let padding = match single_char(padding.as_str()) {
+        Some(padding) => padding,
+        None => panic!("'{}': the padding must be a single character", SIGNATURE),
+    };
+    let padded = match value {
+        Value::String(ref string) => pad_to_width(string.as_str(), width, padding, false),
+        Value::Symbol(sym) => pad_to_width(universe.lookup_symbol(sym), width, padding, false),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    let result = match padded {
+        Ok(padded) => padded.map_or(value, |padded| Value::String(Rc::new(padded))),
+        Err(err) => panic!("'{}': {}", SIGNATURE, err),
+    };
+    frame.borrow_mut().stack.push(result);
+}
+
+/// Compare two strings (or symbols) lexicographically, by Unicode scalar values.
+///
+/// Returns `None` if either of them is neither a string nor a symbol.
+fn compare_strings(universe: &Universe, s1: &Value, s2: &Value) -> Option<Ordering> {
+    fn as_str<'a>(universe: &'a Universe, value: &'a Value) -> Option<&'a str> {
+        match value {
+            Value::String(value) => Some(value.as_str()),
+            Value::Symbol(sym) => Some(universe.lookup_symbol(*sym)),
+            _ => None,
+        }
+    }
+    Some(as_str(universe, s1)?.cmp(as_str(universe, s2)?))
+}
+
+/// Find the (1-based, in characters) position of the first occurrence of `needle` within `value`.
+///
+/// Returns zero if it does not occur, an empty needle being considered to never occur.
+fn index_of_substring(value: &str, needle: &str) -> usize {
+    if needle.is_empty() {
+        return 0;
+    }
+    value
+        .find(needle)
+        .map_or(0, |byte_idx| value[..byte_idx].chars().count() + 1)
+}
+
+/// Get the only character of the given string, if it is made of exactly one.
+fn single_char(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Some(ch),
+        _ => None,
+    }
+}
+
+/// The widest string that padding (or justifying) will produce, in characters.
+const MAX_PADDED_WIDTH: i64 = 1 << 24;
+
+/// Pad a string with the given character (on its left or on its right) until it is `width` characters long.
+///
+/// Returns `None` if the string is already at least that long, and an error if `width` exceeds `MAX_PADDED_WIDTH`.
+pub(crate) fn pad_to_width(
+    value: &str,
+    width: i64,
+    padding: char,
+    left: bool,
+) -> Result<Option<String>, String> {
+    if width > MAX_PADDED_WIDTH {
+        return Err(format!(
+            "the width exceeds the maximum of {} characters",
+            MAX_PADDED_WIDTH
+        ));
+    }
+    let length = value.chars().count();
+    let missing = match usize::try_from(width)
+        .ok()
+        .and_then(|width| width.checked_sub(length))
+    {
+        Some(missing) if missing > 0 => missing,
+        _ => return Ok(None),
+    };
+    let padding = padding.to_string().repeat(missing);
+    if left {
+        Ok(Some(padding + value))
+    } else {
+        Ok(Some(format!("{}{}", value, padding)))
+    }
+}
+
+/// Parse a string as an integer (or a double, if it has a fractional part or an exponent).
+///
+/// Returns `nil` if the string is not a valid number.
+fn parse_number(value: &str) -> Value {
+    let value = value.trim();
+    let parsed = if value.contains(&['.', 'e', 'E'][..]) {
+        value.parse().map(Value::Double).ok()
+    } else {
+        (value.parse().map(Value::Integer))
+            .or_else(|_| value.parse().map(Value::BigInteger))
+            .ok()
+    };
+    parsed.unwrap_or(Value::Nil)
+}
+
+/// An element of the glob patterns understood by `String>>#matchesRegex:`.
+enum GlobToken {
+    /// Matches exactly this character.
+    Char(char),
+    /// Matches any single character (`?`).
+    Any,
+    /// Matches any sequence of characters, including the empty one (`*`).
+    Star,
+    /// Matches a single character within (or outside of, when negated) the given ranges (`[a-z]`, `[!0-9]`).
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+impl GlobToken {
+    /// Whether this (non-star) token matches the given character.
+    fn matches(&self, ch: char) -> bool {
+        match self {
+            GlobToken::Char(expected) => *expected == ch,
+            GlobToken::Any => true,
+            GlobToken::Star => false,
+            GlobToken::Class { negated, ranges } => {
+                ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&ch))
+                    != *negated
+            }
+        }
+    }
+}
+
+/// Parse a glob pattern (`*`, `?`, `[...]` character classes and `\` escapes).
+///
+/// Returns `None` if the pattern is malformed (unterminated class or escape, or reversed range).
+fn parse_glob(pattern: &str) -> Option<Vec<GlobToken>> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut tokens = Vec::new();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let token = match chars[idx] {
+            '*' => GlobToken::Star,
+            '?' => GlobToken::Any,
+            '\\' => {
+                idx += 1;
+                GlobToken::Char(*chars.get(idx)?)
+            }
+            '[' => {
+                idx += 1;
+                let negated = matches!(chars.get(idx), Some('!') | Some('^'));
+                if negated {
+                    idx += 1;
+                }
+                // A `]` right after the opening bracket is taken literally.
+                let mut ranges = Vec::new();
+                loop {
+                    let start = match *chars.get(idx)? {
+                        ']' if !ranges.is_empty() => break,
+                        '\\' => {
+                            idx += 1;
+                            *chars.get(idx)?
+                        }
+                        ch => ch,
+                    };
+                    idx += 1;
+                    let end = match (chars.get(idx), chars.get(idx + 1)) {
+                        (Some('-'), Some(&end)) if end != ']' => {
+                            idx += 2;
+                            end
+                        }
+                        _ => start,
+                    };
+                    if end < start {
+                        return None;
+                    }
+                    ranges.push((start, end));
+                }
+                GlobToken::Class { negated, ranges }
+            }
+            ch => GlobToken::Char(ch),
+        };
+        tokens.push(token);
+        idx += 1;
+    }
+    Some(tokens)
+}
+
+/// Whether the glob pattern matches the entirety of `value`.
+fn glob_matches(tokens: &[GlobToken], value: &str) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let (mut token_idx, mut value_idx) = (0, 0);
+    // The position of the last star seen, and of the character it currently stops before.
+    let mut backtrack = None;
+    while value_idx < value.len() {
+        match tokens.get(token_idx) {
+            Some(GlobToken::Star) => {
+                backtrack = Some((token_idx, value_idx));
+                token_idx += 1;
+            }
+            Some(token) if token.matches(value[value_idx]) => {
+                token_idx += 1;
+                value_idx += 1;
+            }
+            _ => match backtrack {
+                Some((star_idx, stop_idx)) => {
+                    backtrack = Some((star_idx, stop_idx + 1));
+                    token_idx = star_idx + 1;
+                    value_idx = stop_idx + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[token_idx..]
+        .iter()
+        .all(|token| matches!(token, GlobToken::Star))
+}
+
+fn join(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "String>>#join:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        separator => separator,
+        Value::Array(values) => values,
+    ]);
+
+    let separator = match separator {
+        Value::String(ref value) => value.as_str(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong types", SIGNATURE),
+    };
+
+    let joined = join_values(universe, separator, &values.borrow());
+    frame
+        .borrow_mut()
+        .stack
+        .push(Value::String(Rc::new(joined)))
+}
+
+/// Join the given values with the separator, using the strings' contents as-is and rendering the other values.
+fn join_values(universe: &Universe, separator: &str, values: &[Value]) -> String {
+    let strings: Vec<String> = values
+        .iter()
+        .map(|value| match value {
+            Value::String(value) => value.to_string(),
+            value => value.to_string(universe),
+        })
+        .collect();
+    strings.join(separator)
+}
+
+/// The primitives this interpreter provides for the **String** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "asNumber",
+    "reverse",
+    "printString",
+    "occurrencesOf:",
+    "indexOf:",
+    "indexOfSubCollection:",
+    "matchesRegex:",
+    "leftPad:with:",
+    "rightPad:with:",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "join:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -213,6 +755,21 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "concatenate:" => Some(self::concatenate),
         "primSubstringFrom:to:" => Some(self::prim_substring_from_to),
         "=" => Some(self::eq),
+        "<" => Some(self::lt),
+        "<=" => Some(self::lte),
+        ">" => Some(self::gt),
+        ">=" => Some(self::gte),
+        "join:" => Some(self::join),
+        "asNumber" => Some(self::as_number),
+        "reverse" => Some(self::reverse),
+        "printString" => Some(self::print_string),
+        "occurrencesOf:" => Some(self::occurrences_of),
+        "indexOf:" => Some(self::index_of),
+        // `indexOfSubCollection:` is an alias of `indexOf:`.
+        "indexOfSubCollection:" => Some(self::index_of),
+        "matchesRegex:" => Some(self::matches_regex),
+        "leftPad:with:" => Some(self::left_pad),
+        "rightPad:with:" => Some(self::right_pad),
         _ => None,
     }
 }
@@ -20,10 +20,29 @@ fn as_string(interpreter: &mut Interpreter, universe: &mut Universe) {
     )));
 }
 
+fn eq(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "Symbol>>#=";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::Symbol(sym) => sym,
+        other => other,
+    ]);
+
+    // Symbols are interned, so equal symbols share the same ID, and a symbol never equals a string.
+    let eq = matches!(other, Value::Symbol(other) if other == sym);
+    frame.borrow_mut().stack.push(Value::Boolean(eq));
+}
+
+/// The primitives this interpreter provides for the **Symbol** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &["="];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
         "asString" => Some(self::as_string),
+        "=" => Some(self::eq),
         _ => None,
     }
 }
@@ -1,7 +1,9 @@
 use std::convert::TryFrom;
+use std::time::{SystemTime, UNIX_EPOCH};
 // use std::io::BufRead;
-// use std::rc::Rc;
+use std::rc::Rc;
 
+use crate::compiler::WELL_KNOWN_GLOBALS;
 use crate::interpreter::Interpreter;
 use crate::primitives::PrimitiveFn;
 use crate::universe::Universe;
@@ -70,6 +72,34 @@ fn load(interpreter: &mut Interpreter, universe: &mut Universe) {
     }
 }
 
+fn load_module(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "System>>#loadModule:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::System,
+        name => name,
+    ]);
+
+    let name = match name {
+        Value::String(ref name) => name.to_string(),
+        Value::Symbol(sym) => universe.lookup_symbol(sym).to_string(),
+        _ => panic!("'{}': wrong type", SIGNATURE),
+    };
+
+    // Classes that are already loaded are not loaded again, so that existing references to them stay valid.
+    let symbol = universe.intern_symbol(name.as_str());
+    let class = match universe.lookup_global(symbol) {
+        Some(Value::Class(class)) => Some(class),
+        _ => universe.load_class(name).ok(),
+    };
+    frame
+        .borrow_mut()
+        .stack
+        .push(class.map_or(Value::Nil, Value::Class))
+}
+
 fn global(interpreter: &mut Interpreter, universe: &mut Universe) {
     const SIGNATURE: &str = "System>>#global:";
 
@@ -97,6 +127,19 @@ fn global_put(interpreter: &mut Interpreter, universe: &mut Universe) {
         value => value,
     ]);
 
+    let name = universe.lookup_symbol(sym);
+    if WELL_KNOWN_GLOBALS.contains(&name) {
+        // Also reached through `Smalltalk at:put:`, so the message does not name the selector.
+        let message = format!("cannot rebind the well-known global '{}'", name);
+        if universe
+            .error(interpreter, Value::System, message.clone())
+            .is_none()
+        {
+            panic!("{}", message);
+        }
+        return;
+    }
+
     universe.assign_global(sym, value.clone());
     frame.borrow_mut().stack.push(value)
 }
@@ -111,6 +154,9 @@ fn exit(interpreter: &mut Interpreter, _: &mut Universe) {
         Value::Integer(code) => code,
     ]);
 
+    #[cfg(feature = "bc-coverage")]
+    crate::interpreter::report_bytecode_counts();
+
     match i32::try_from(code) {
         Ok(code) => std::process::exit(code),
         Err(err) => panic!("'{}': {}", SIGNATURE, err),
@@ -154,6 +200,62 @@ fn full_gc(interpreter: &mut Interpreter, _: &mut Universe) {
     frame.borrow_mut().stack.push(Value::Boolean(false))
 }
 
+fn current_time_millis(interpreter: &mut Interpreter, _: &mut Universe) {
+    const SIGNATURE: &str = "System>>#currentTimeMillis";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [Value::System]);
+
+    frame.borrow_mut().stack.push(unix_time_millis())
+}
+
+/// Get the current Unix time in milliseconds (negative if the clock is set before the epoch).
+fn unix_time_millis() -> Value {
+    let millis = match SystemTime::now().duration_since(UNIX_EPOCH) {
+        Ok(duration) => duration.as_millis() as i128,
+        Err(err) => -(err.duration().as_millis() as i128),
+    };
+    i64::try_from(millis)
+        .map(Value::Integer)
+        .unwrap_or_else(|_| Value::BigInteger(millis.into()))
+}
+
+fn environment_variable(interpreter: &mut Interpreter, universe: &mut Universe) {
+    const SIGNATURE: &str = "System>>#environmentVariable:";
+
+    let frame = interpreter.current_frame().expect("no current frame");
+
+    expect_args!(SIGNATURE, frame, [
+        Value::System,
+        name => name,
+    ]);
+
+    if !crate::primitives::allow_environment() {
+        panic!("'{}': access to the environment is disabled", SIGNATURE);
+    }
+
+    let name = match name {
+        Value::String(ref string) => string,
+        Value::Symbol(sym) => universe.lookup_symbol(sym),
+        _ => panic!("'{}': wrong type", SIGNATURE),
+    };
+
+    let value = std::env::var(name)
+        .map(|value| Value::String(Rc::new(value)))
+        .unwrap_or(Value::Nil);
+    frame.borrow_mut().stack.push(value)
+}
+
+/// The primitives this interpreter provides for the **System** class, beyond those declared by the core library.
+pub static EXTENSIONS: &[&str] = &[
+    "currentTimeMillis",
+    "environmentVariable:",
+    "at:",
+    "at:put:",
+    "loadModule:",
+];
+
 /// Search for a primitive matching the given signature.
 pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
     match signature.as_ref() {
@@ -161,12 +263,18 @@ pub fn get_primitive(signature: impl AsRef<str>) -> Option<PrimitiveFn> {
         "printString:" => Some(self::print_string),
         "printNewline" => Some(self::print_newline),
         "load:" => Some(self::load),
+        "loadModule:" => Some(self::load_module),
         "ticks" => Some(self::ticks),
         "time" => Some(self::time),
+        "currentTimeMillis" => Some(self::current_time_millis),
+        "environmentVariable:" => Some(self::environment_variable),
         "fullGC" => Some(self::full_gc),
         "exit:" => Some(self::exit),
         "global:" => Some(self::global),
         "global:put:" => Some(self::global_put),
+        // `Smalltalk at:` and `Smalltalk at:put:`, the dictionary-like view of the globals.
+        "at:" => Some(self::global),
+        "at:put:" => Some(self::global_put),
         _ => None,
     }
 }
@@ -16,6 +16,93 @@ use crate::interpreter::Interpreter;
 use crate::value::Value;
 use crate::SOMRef;
 
+/// The selectors of the conditionals only understood by booleans.
+const BOOLEAN_CONDITIONALS: &[&str] =
+    &["ifTrue:", "ifFalse:", "ifTrue:ifFalse:", "ifFalse:ifTrue:"];
+
+/// The range of integers whose string representations are cached by [`Universe::integer_string`].
+const SMALL_INTEGERS: std::ops::RangeInclusive<i64> = -128..=256;
+
+/// Methods taking blocks, which primitives cannot provide since they cannot call back into the interpreter,
+/// as `(class name, signature, source)`.
+/// Each is installed at startup unless the core library's class already defines it.
+const CORE_EXTENSIONS: &[(&str, &str, &str)] = &[
+    (
+        "Array",
+        "doWithIndex:",
+        "doWithIndex: block = ( 1 to: self length do: [ :i | block value: (self at: i) with: i ] )",
+    ),
+    (
+        "Array",
+        "allSatisfy:",
+        "allSatisfy: block = ( self do: [ :e | (block value: e) ifFalse: [ ^false ] ]. ^true )",
+    ),
+    (
+        "Array",
+        "anySatisfy:",
+        "anySatisfy: block = ( self do: [ :e | (block value: e) ifTrue: [ ^true ] ]. ^false )",
+    ),
+    (
+        "Array",
+        "withIndexCollect:",
+        "withIndexCollect: block = ( | result | result := Array new: self length. 1 to: self length do: [ :i | result at: i put: (block value: (self at: i) with: i) ]. ^result )",
+    ),
+    (
+        "Array",
+        "at:ifAbsent:",
+        "at: index ifAbsent: block = ( (index < 1 or: [ index > self length ]) ifTrue: [ ^block value ]. ^self at: index )",
+    ),
+    (
+        "Array",
+        "partition:",
+        concat!(
+            "partition: block = ( | flags count matching rest result | ",
+            "flags := self collect: [ :e | | flag | flag := block value: e. ",
+            "(flag == true or: [ flag == false ]) ifFalse: [ self error: 'the block given to partition: did not return a boolean' ]. flag ]. ",
+            "count := 0. flags do: [ :flag | flag ifTrue: [ count := count + 1 ] ]. ",
+            "matching := Array new: count. rest := Array new: self length - count. count := 0. ",
+            "1 to: self length do: [ :i | (flags at: i) ",
+            "ifTrue: [ count := count + 1. matching at: count put: (self at: i) ] ",
+            "ifFalse: [ rest at: i - count put: (self at: i) ] ]. ",
+            "result := Array new: 2. result at: 1 put: matching. result at: 2 put: rest. ^result )",
+        ),
+    ),
+    (
+        "String",
+        "do:",
+        "do: block = ( 1 to: self length do: [ :i | block value: (self primSubstringFrom: i to: i) ] )",
+    ),
+];
+
+/// The source code of the **Context** class, whose instances are returned by `Object>>#thisContext`.
+const CONTEXT_CLASS: &str = "Context = ( | signature bytecodeIndex sender | signature = ( ^ signature ) bytecodeIndex = ( ^ bytecodeIndex ) sender = ( ^ sender ) )";
+
+/// The error reported when a global cannot be resolved, with strict globals.
+fn unknown_global_message(name: &str) -> String {
+    format!(
+        "the global '{}' is not defined (strict globals are enabled)",
+        name
+    )
+}
+
+/// The error reported when a boolean conditional is sent to a value which is not a boolean.
+fn non_boolean_receiver_message(signature: &str, class: &SOMRef<Class>) -> String {
+    format!(
+        "'{}' was sent to an instance of {}, but a Boolean was expected",
+        signature,
+        class.borrow().name(),
+    )
+}
+
+/// The error reported when a message is not understood by a value which has no `doesNotUnderstand:arguments:`.
+fn missing_handler_message(signature: &str, class: &SOMRef<Class>) -> String {
+    format!(
+        "could not find method '{}>>#{}', and its receiver does not define 'doesNotUnderstand:arguments:'",
+        class.borrow().name(),
+        signature,
+    )
+}
+
 /// The core classes of the SOM interpreter.
 ///
 /// This struct allows to always keep a reference to important classes,
@@ -76,8 +163,17 @@ pub struct Universe {
     pub globals: HashMap<Interned, Value>,
     /// The path to search in for new classes.
     pub classpath: Vec<PathBuf>,
+    /// The cached string representations of small integers.
+    small_integer_strings: Vec<Option<Rc<String>>>,
     /// The interpreter's core classes.
     pub core: CoreClasses,
+    /// Whether an unresolved global is an error, instead of being handed to `unknownGlobal:`.
+    pub strict_globals: bool,
+    /// The **Context** class, once loaded by `Object>>#thisContext`.
+    context_class: Option<SOMRef<Class>>,
+    /// The number of invocations of each defined method, keyed by (holder name, signature).
+    #[cfg(feature = "coverage")]
+    pub method_invocation_count: HashMap<(Interned, Interned), u64>,
 }
 
 impl Universe {
@@ -182,12 +278,16 @@ impl Universe {
             globals.insert(interner.intern("false"), Value::Boolean(false));
             globals.insert(interner.intern("nil"), Value::Nil);
             globals.insert(interner.intern("system"), Value::System);
+            globals.insert(interner.intern("Smalltalk"), Value::System);
         };
 
-        Ok(Self {
+        let mut universe = Self {
             globals,
             interner,
             classpath,
+            small_integer_strings: vec![None; SMALL_INTEGERS.count()],
+            strict_globals: false,
+            context_class: None,
             core: CoreClasses {
                 object_class,
                 class_class,
@@ -209,7 +309,47 @@ impl Universe {
                 true_class,
                 false_class,
             },
-        })
+            #[cfg(feature = "coverage")]
+            method_invocation_count: HashMap::new(),
+        };
+        universe.install_core_extensions()?;
+
+        Ok(universe)
+    }
+
+    /// Install the methods from `CORE_EXTENSIONS` that the core library does not already define.
+    fn install_core_extensions(&mut self) -> Result<(), Error> {
+        for (class_name, signature, source) in CORE_EXTENSIONS {
+            let name = self.intern_symbol(class_name);
+            let signature = self.intern_symbol(signature);
+            let defined = match self.lookup_global(name) {
+                Some(Value::Class(class)) => class.borrow().methods.contains_key(&signature),
+                _ => false,
+            };
+            if !defined {
+                self.install_class_extension(class_name, source)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Enumerate the places where a class' source code may be found, in lookup order.
+    ///
+    /// The embedded core library (if enabled) comes first, with its source code given directly,
+    /// followed by the corresponding file within each of the given classpath entries.
+    fn class_sources(
+        class_name: &str,
+        classpath: impl Iterator<Item = PathBuf>,
+    ) -> impl Iterator<Item = (PathBuf, Option<&'static str>)> {
+        let file_name = format!("{}.som", class_name);
+        let embedded = som_core::corelib::lookup(class_name).map(|contents| {
+            (
+                PathBuf::from("<embedded>").join(file_name.as_str()),
+                Some(contents),
+            )
+        });
+        let on_disk = classpath.map(move |path| (path.join(file_name.as_str()), None));
+        embedded.into_iter().chain(on_disk)
     }
 
     /// Load a system class (with an incomplete hierarchy).
@@ -219,20 +359,21 @@ impl Universe {
         class_name: impl Into<String>,
     ) -> Result<SOMRef<Class>, Error> {
         let class_name = class_name.into();
-        for path in classpath {
-            let mut path = path.as_ref().join(class_name.as_str());
-            path.set_extension("som");
-
+        let paths = classpath.iter().map(|path| path.as_ref().to_path_buf());
+        for (path, embedded) in Self::class_sources(class_name.as_str(), paths) {
             // Read file contents.
-            let contents = match fs::read_to_string(path.as_path()) {
-                Ok(contents) => contents,
-                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
-                Err(err) => return Err(Error::from(err)),
+            let contents = match embedded {
+                Some(contents) => contents.to_string(),
+                None => match fs::read_to_string(path.as_path()) {
+                    Ok(contents) => contents,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                    Err(err) => return Err(Error::from(err)),
+                },
             };
 
             // Collect all tokens from the file.
             let tokens: Vec<_> = som_lexer::Lexer::new(contents.as_str())
-                .skip_comments(true)
+                .skip_comments(false)
                 .skip_whitespace(true)
                 .collect();
 
@@ -260,19 +401,20 @@ impl Universe {
     /// Load a class from its name into this universe.
     pub fn load_class(&mut self, class_name: impl Into<String>) -> Result<SOMRef<Class>, Error> {
         let class_name = class_name.into();
-        for path in self.classpath.iter() {
-            let mut path = path.join(class_name.as_str());
-            path.set_extension("som");
-
+        let paths = self.classpath.clone().into_iter();
+        for (path, embedded) in Self::class_sources(class_name.as_str(), paths) {
             // Read file contents.
-            let contents = match fs::read_to_string(path.as_path()) {
-                Ok(contents) => contents,
-                Err(_) => continue,
+            let contents = match embedded {
+                Some(contents) => contents.to_string(),
+                None => match fs::read_to_string(path.as_path()) {
+                    Ok(contents) => contents,
+                    Err(_) => continue,
+                },
             };
 
             // Collect all tokens from the file.
             let tokens: Vec<_> = som_lexer::Lexer::new(contents.as_str())
-                .skip_comments(true)
+                .skip_comments(false)
                 .skip_whitespace(true)
                 .collect();
 
@@ -363,7 +505,7 @@ impl Universe {
 
         // Collect all tokens from the file.
         let tokens: Vec<_> = som_lexer::Lexer::new(contents.as_str())
-            .skip_comments(true)
+            .skip_comments(false)
             .skip_whitespace(true)
             .collect();
 
@@ -397,6 +539,93 @@ impl Universe {
         Ok(class)
     }
 
+    /// Load a class from its source code into this universe, without going through the classpath.
+    ///
+    /// The class' superclass must already be loaded.
+    pub fn load_class_from_source(&mut self, source: &str) -> Result<SOMRef<Class>, Error> {
+        let class = self.compile_class_from_source(source)?;
+
+        let symbol = self.intern_symbol(class.borrow().name());
+        self.globals.insert(symbol, Value::Class(class.clone()));
+
+        Ok(class)
+    }
+
+    /// Compile a class from its source code, without registering it as a global.
+    fn compile_class_from_source(&mut self, source: &str) -> Result<SOMRef<Class>, Error> {
+        // Collect all tokens from the source.
+        let tokens: Vec<_> = som_lexer::Lexer::new(source)
+            .skip_comments(false)
+            .skip_whitespace(true)
+            .collect();
+
+        // Parse class definition from the tokens.
+        let defn = match som_parser::parse_file(tokens.as_slice()) {
+            Some(defn) => defn,
+            None => return Err(Error::msg("could not parse class source")),
+        };
+
+        let super_class = if let Some(ref super_class) = defn.super_class {
+            let symbol = self.intern_symbol(super_class);
+            match self.lookup_global(symbol) {
+                Some(Value::Class(class)) => class,
+                _ => {
+                    return Err(anyhow!(
+                        "could not find the '{}' class (superclass of '{}')",
+                        super_class,
+                        defn.name,
+                    ))
+                }
+            }
+        } else {
+            self.core.object_class.clone()
+        };
+
+        let class = compiler::compile_class(&mut self.interner, &defn, Some(&super_class))
+            .ok_or_else(|| anyhow!("could not compile the '{}' class", defn.name))?;
+        set_super_class(&class, &super_class, &self.core.metaclass_class);
+
+        Ok(class)
+    }
+
+    /// Add methods to an existing class, from the source code of a class extension.
+    ///
+    /// The extension is written like the body of a class definition (its instance methods, optionally
+    /// followed by `----` and its class methods), but cannot declare any fields.
+    /// Methods with the same signatures as existing ones replace them.
+    pub fn install_class_extension(&mut self, class_name: &str, source: &str) -> Result<(), Error> {
+        let symbol = self.intern_symbol(class_name);
+        let class = match self.lookup_global(symbol) {
+            Some(Value::Class(class)) => class,
+            _ => self.load_class(class_name)?,
+        };
+
+        // Parse the extension as a definition of the extended class.
+        let source = format!("{} = ( {} )", class_name, source);
+        let tokens: Vec<_> = som_lexer::Lexer::new(source.as_str())
+            .skip_comments(false)
+            .skip_whitespace(true)
+            .collect();
+        let defn = match som_parser::parse_file(tokens.as_slice()) {
+            Some(defn) => defn,
+            None => return Err(Error::msg("could not parse class extension")),
+        };
+
+        if !defn.instance_locals.is_empty() || !defn.static_locals.is_empty() {
+            return Err(anyhow!(
+                "the extension of the '{}' class cannot declare fields",
+                class_name
+            ));
+        }
+
+        compiler::compile_class_extension(&mut self.interner, &defn, &class).ok_or_else(|| {
+            anyhow!(
+                "could not compile the extension of the '{}' class",
+                class_name
+            )
+        })
+    }
+
     /// Get the **Nil** class.
     pub fn nil_class(&self) -> SOMRef<Class> {
         self.core.nil_class.clone()
@@ -472,6 +701,20 @@ impl Universe {
     pub fn primitive_class(&self) -> SOMRef<Class> {
         self.core.primitive_class.clone()
     }
+
+    /// Get the **Context** class (loading it on first use).
+    ///
+    /// It is not registered as a global, so that it cannot clash with a user-defined **Context** class.
+    pub fn context_class(&mut self) -> SOMRef<Class> {
+        if let Some(class) = self.context_class.as_ref() {
+            return class.clone();
+        }
+        let class = self
+            .compile_class_from_source(CONTEXT_CLASS)
+            .expect("could not load the 'Context' class");
+        self.context_class = Some(class.clone());
+        class
+    }
 }
 
 impl Universe {
@@ -480,11 +723,60 @@ impl Universe {
         self.interner.intern(symbol)
     }
 
+    /// Intern a batch of symbols, returning them in the same order.
+    pub fn intern_symbols(&mut self, symbols: &[&str]) -> Vec<Interned> {
+        self.interner.intern_all(symbols)
+    }
+
     /// Lookup a symbol.
     pub fn lookup_symbol(&self, symbol: Interned) -> &str {
         self.interner.lookup(symbol)
     }
 
+    /// Get the string representation of an integer.
+    ///
+    /// Small integers share a single, lazily-built, string per value.
+    pub fn integer_string(&mut self, value: i64) -> Rc<String> {
+        if !SMALL_INTEGERS.contains(&value) {
+            return Rc::new(value.to_string());
+        }
+        let index = (value - SMALL_INTEGERS.start()) as usize;
+        self.small_integer_strings[index]
+            .get_or_insert_with(|| Rc::new(value.to_string()))
+            .clone()
+    }
+
+    /// Record an invocation of a defined method.
+    #[cfg(feature = "coverage")]
+    pub fn record_invocation(&mut self, holder: &SOMRef<Class>, signature: Interned) {
+        let holder = self.interner.intern(holder.borrow().name());
+        *self
+            .method_invocation_count
+            .entry((holder, signature))
+            .or_insert(0) += 1;
+    }
+
+    /// Write the invocation counts of all executed methods, one `Holder>>#signature count` per line.
+    #[cfg(feature = "coverage")]
+    pub fn dump_method_invocation_count(&self, mut output: impl io::Write) -> io::Result<()> {
+        let mut entries: Vec<_> = self
+            .method_invocation_count
+            .iter()
+            .map(|((holder, signature), count)| {
+                (
+                    self.lookup_symbol(*holder),
+                    self.lookup_symbol(*signature),
+                    *count,
+                )
+            })
+            .collect();
+        entries.sort_unstable();
+        for (holder, signature, count) in entries {
+            writeln!(output, "{}>>#{} {}", holder, signature, count)?;
+        }
+        Ok(())
+    }
+
     /// Search for a global binding.
     pub fn lookup_global(&self, idx: Interned) -> Option<Value> {
         self.globals.get(&idx).cloned()
@@ -499,16 +791,23 @@ impl Universe {
 
 impl Universe {
     /// Call `escapedBlock:` on the given value, if it is defined.
+    ///
+    /// If the value only has the generic definition from **Object**, `error:` is called instead with the given
+    /// message, which can tell where the block came from.
     pub fn escaped_block(
         &mut self,
         interpreter: &mut Interpreter,
         value: Value,
         block: Rc<Block>,
+        message: String,
     ) -> Option<()> {
         let method_name = self.intern_symbol("escapedBlock:");
         let method = value.lookup_method(self, method_name)?;
 
         let holder = method.holder().upgrade().unwrap();
+        if Rc::ptr_eq(&holder, &self.core.object_class) {
+            return self.error(interpreter, value, message);
+        }
         let kind = FrameKind::Method {
             method,
             holder,
@@ -522,7 +821,7 @@ impl Universe {
         Some(())
     }
 
-    /// Call `doesNotUnderstand:` on the given value, if it is defined.
+    /// Call `doesNotUnderstand:` on the given value, or `error:` if the former is not defined.
     pub fn does_not_understand(
         &mut self,
         interpreter: &mut Interpreter,
@@ -531,9 +830,23 @@ impl Universe {
         args: Vec<Value>,
     ) -> Option<()> {
         let method_name = self.intern_symbol("doesNotUnderstand:arguments:");
-        let method = value.lookup_method(self, method_name)?;
+        let method = match value.lookup_method(self, method_name) {
+            Some(method) => method,
+            None => {
+                let message =
+                    missing_handler_message(self.lookup_symbol(symbol), &value.class(self));
+                return self.error(interpreter, value, message);
+            }
+        };
 
         let holder = method.holder().upgrade().unwrap();
+        let signature = self.lookup_symbol(symbol);
+        if BOOLEAN_CONDITIONALS.contains(&signature) && Rc::ptr_eq(&holder, &self.core.object_class)
+        {
+            let message = non_boolean_receiver_message(signature, &value.class(self));
+            return self.error(interpreter, value, message);
+        }
+
         let kind = FrameKind::Method {
             method,
             holder,
@@ -549,13 +862,54 @@ impl Universe {
         Some(())
     }
 
+    /// Call `error:` on the given value, if it is defined.
+    pub fn error(
+        &mut self,
+        interpreter: &mut Interpreter,
+        value: Value,
+        message: String,
+    ) -> Option<()> {
+        let method_name = self.intern_symbol("error:");
+        let method = value.lookup_method(self, method_name)?;
+
+        let holder = method.holder().upgrade().unwrap();
+        let kind = FrameKind::Method {
+            method,
+            holder,
+            self_value: value.clone(),
+        };
+
+        let frame = interpreter.push_frame(kind);
+        frame.borrow_mut().args.push(value);
+        frame
+            .borrow_mut()
+            .args
+            .push(Value::String(Rc::new(message)));
+
+        Some(())
+    }
+
     /// Call `unknownGlobal:` on the given value, if it is defined.
+    ///
+    /// With strict globals, the global is only looked up as a class in the classpath, and reported as an error if not found.
     pub fn unknown_global(
         &mut self,
         interpreter: &mut Interpreter,
         value: Value,
         name: Interned,
     ) -> Option<()> {
+        if self.strict_globals {
+            let class_name = self.lookup_symbol(name).to_string();
+            return match self.load_class(class_name.as_str()) {
+                Ok(class) => {
+                    let frame = interpreter.current_frame()?;
+                    frame.borrow_mut().stack.push(Value::Class(class));
+                    Some(())
+                }
+                Err(_) => self.error(interpreter, value, unknown_global_message(&class_name)),
+            };
+        }
+
         let method_name = self.intern_symbol("unknownGlobal:");
         let method = value.lookup_method(self, method_name)?;
 
@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::rc::Rc;
 
@@ -63,11 +64,29 @@ impl Value {
         }
     }
 
+    /// Whether this value is `nil`.
+    pub fn is_nil(&self) -> bool {
+        matches!(self, Self::Nil)
+    }
+
     /// Search for a given method for this value.
     pub fn lookup_method(&self, universe: &Universe, signature: Interned) -> Option<Rc<Method>> {
         self.class(universe).borrow().lookup_method(signature)
     }
 
+    /// Get this value as a (non-negative) index, accepting both integers and integral doubles.
+    pub fn as_index(&self) -> Option<usize> {
+        match *self {
+            Self::Integer(value) => usize::try_from(value).ok(),
+            Self::Double(value)
+                if value.fract() == 0.0 && value >= 0.0 && value < usize::MAX as f64 =>
+            {
+                Some(value as usize)
+            }
+            _ => None,
+        }
+    }
+
     /// Search for a local binding within this value.
     pub fn lookup_local(&self, idx: usize) -> Option<Self> {
         match self {
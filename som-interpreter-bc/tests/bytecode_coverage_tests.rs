@@ -0,0 +1,77 @@
+#![cfg(feature = "bc-coverage")]
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+
+use som_core::bytecode::{Bytecode, NAMES, NB_BYTECODES};
+use som_interpreter_bc::frame::FrameKind;
+use som_interpreter_bc::instance::Instance;
+use som_interpreter_bc::interpreter::{self, Interpreter, BYTECODE_COUNTS};
+use som_interpreter_bc::universe::Universe;
+use som_interpreter_bc::value::Value;
+
+fn setup_universe() -> Universe {
+    let classpath = vec![PathBuf::from("../core-lib/Smalltalk")];
+    Universe::with_classpath(classpath).expect("could not setup test universe")
+}
+
+#[test]
+fn bytecode_dispatch_count() {
+    let mut universe = setup_universe();
+
+    let class = universe
+        .load_class_from_source(
+            "BytecodeCoverageTest = ( | field | run = ( | local | local := 3. field := [ :x | x + local ] value: 4. ^ field ) )",
+        )
+        .expect("could not load test class");
+
+    let method_name = universe.intern_symbol("run");
+    let method = class
+        .borrow()
+        .lookup_method(method_name)
+        .expect("method not found ??");
+    let instance = Value::Instance(Rc::new(RefCell::new(Instance::from_class(class.clone()))));
+    let kind = FrameKind::Method {
+        method,
+        holder: class,
+        self_value: instance.clone(),
+    };
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(kind);
+    frame.borrow_mut().args.push(instance);
+    let output = interpreter.run(&mut universe);
+    assert_eq!(output, Some(Value::Integer(7)));
+
+    let count =
+        |bytecode: Bytecode| BYTECODE_COUNTS[bytecode.opcode() as usize].load(Ordering::Relaxed);
+    for bytecode in [
+        Bytecode::PushConstant(0),
+        Bytecode::PushBlock(0),
+        Bytecode::PushLocal(0, 0),
+        Bytecode::PushArgument(0, 0),
+        Bytecode::PopLocal(0, 0),
+        Bytecode::PopField(0),
+        Bytecode::Send(0),
+        Bytecode::ReturnLocal,
+    ] {
+        assert_eq!(NAMES[bytecode.opcode() as usize], bytecode.name());
+        assert!(
+            count(bytecode) > 0,
+            "'{}' was never counted",
+            bytecode.name()
+        );
+    }
+
+    let mut dump = Vec::new();
+    interpreter::dump_bytecode_counts(&mut dump).expect("could not dump bytecode counts");
+    let dump = String::from_utf8(dump).expect("invalid UTF-8 in dump");
+    let names: Vec<_> = dump
+        .lines()
+        .map(|line| line.split_once(' ').expect("malformed line").0)
+        .collect();
+    assert_eq!(names.len(), NB_BYTECODES);
+    assert_eq!(names, NAMES);
+}
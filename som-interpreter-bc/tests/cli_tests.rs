@@ -0,0 +1,583 @@
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn warn_missing_primitives() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("MissingPrimitive.som");
+    fs::write(&file, "MissingPrimitive = ( foo = primitive run = ( ) )")
+        .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--warn-missing-primitives")
+        .arg(&file)
+        .output()
+        .expect("could not run interpreter");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains(
+            "warning: primitive 'MissingPrimitive>>#foo' is declared but not implemented"
+        ),
+        "missing warning in stderr: {}",
+        stderr
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg(&file)
+        .output()
+        .expect("could not run interpreter");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stderr.contains("MissingPrimitive>>#foo"),
+        "unexpected warning in stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn quiet_suppresses_diagnostics() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("QuietProgram.som");
+    fs::write(
+        &file,
+        "QuietProgram = ( foo = primitive run = ( 'hello' println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--warn-missing-primitives")
+        .arg("--quiet")
+        .arg(&file)
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(
+        output.stderr.is_empty(),
+        "unexpected output in stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "hello\n");
+}
+
+#[test]
+fn custom_entry_point() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("EntryPoint.som");
+    fs::write(
+        &file,
+        "EntryPoint = ( run = ( 'run' println ) other = ( (20 + 22) println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--entry")
+        .arg("other")
+        .arg(&file)
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--entry")
+        .arg("missing")
+        .arg(&file)
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("could not find method 'EntryPoint>>#missing'"),
+        "missing error in stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn bench_reports_iterations_per_second() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("TrivialBench.som");
+    fs::write(
+        &file,
+        "TrivialBench = ( benchmark = ( ^ 20 + 22 ) verifyResult: result = ( ^ result = 42 ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("-c")
+        .arg(&directory)
+        .arg("--bench")
+        .arg("TrivialBench")
+        .arg("--bench-duration")
+        .arg("50")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(
+        output.status.success(),
+        "benchmark failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let line = stdout.trim_end();
+    let fields = line
+        .strip_prefix("TrivialBench: ")
+        .unwrap_or_else(|| panic!("unexpected benchmark output: {}", line));
+    let keys: Vec<_> = fields
+        .split(' ')
+        .map(|field| {
+            let (key, value) = field.split_once('=').expect("malformed field");
+            assert!(value.parse::<f64>().is_ok(), "malformed value: {}", field);
+            key
+        })
+        .collect();
+    assert_eq!(keys, ["iterations", "elapsed_us", "iterations_per_sec"]);
+}
+
+#[test]
+fn escaped_block_diagnostic() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("EscapingBlock.som");
+    fs::write(
+        &file,
+        "EscapingBlock = ( makeBlock = ( ^ [ ^ 42 ] ) run = ( self makeBlock value ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "ERROR: a block defined in 'EscapingBlock>>#makeBlock' attempted a non-local return from that method, which has already returned"
+        ),
+        "missing error in stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn dump_literals_lists_literal_tables() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("LiteralTable.som");
+    fs::write(
+        &file,
+        "LiteralTable = ( run = ( #world println. ^ #(1 2) , 'hello' ) ---- new = ( ^ [ 2.5 ] value ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--dump-literals")
+        .output()
+        .expect("could not run interpreter");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let expected = concat!(
+        "LiteralTable>>#run\n",
+        "    0    Symbol   #world\n",
+        "    1    Symbol   #println\n",
+        "    2    Integer  1\n",
+        "    3    Integer  2\n",
+        "    4    Array    [2, 3]\n",
+        "    5    String   \"hello\"\n",
+        "    6    Symbol   #,\n",
+        "LiteralTable class>>#new\n",
+        "    0    Block    <block>\n",
+        "    1    Symbol   #value\n",
+    );
+    assert_eq!(stdout, expected);
+}
+
+#[test]
+fn preload_loads_classes_before_running() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    fs::write(directory.join("Preloaded.som"), "Preloaded = ( )")
+        .expect("could not write test class");
+    let file = directory.join("PreloadCheck.som");
+    fs::write(
+        &file,
+        "PreloadCheck = ( run = ( (system global: #Preloaded) isNil println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--preload")
+        .arg("Preloaded")
+        .output()
+        .expect("could not run interpreter");
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "false\n");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--preload")
+        .arg("NonExistent")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("could not preload class 'NonExistent'"),
+        "missing error in stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn conditional_on_non_boolean_reports_clear_error() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("NonBooleanConditional.som");
+    fs::write(
+        &file,
+        "NonBooleanConditional = ( run = ( (42 ifTrue: [ 1 ] ifFalse: [ 2 ]) println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(
+            "ERROR: 'ifTrue:ifFalse:' was sent to an instance of Integer, but a Boolean was expected"
+        ),
+        "missing error in stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn list_primitives_prints_primitive_table() {
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg("--list-primitives")
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<_> = stdout.lines().collect();
+    assert!(
+        lines.contains(&"Integer >> + (instance-side)"),
+        "missing primitive in stdout: {}",
+        stdout
+    );
+    assert!(
+        lines.contains(&"Array >> new: (class-side)"),
+        "missing primitive in stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn value_with_timeout_aborts_infinite_loop() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("TimedLoop.som");
+    fs::write(
+        &file,
+        "TimedLoop = ( run = ( ([ 42 ] valueWithTimeout: 1000) println. [ [ true ] whileTrue: [ ] ] valueWithTimeout: 20. 'unreachable' println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("42\n"), "unexpected stdout: {}", stdout);
+    assert!(
+        stdout.contains("ERROR: block exceeded its time budget of 20 ms"),
+        "missing error in stdout: {}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("unreachable"),
+        "unexpected stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn dump_inline_cache_reports_filled_entries() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("GlobalCache.som");
+    fs::write(
+        &file,
+        "GlobalCache = ( run = ( (Array new: 2) length println. [ nil ] value ) unused = ( ^ Object ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--dump-inline-cache")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "2\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let lines: Vec<_> = stderr.lines().collect();
+    assert!(
+        lines.contains(&"GlobalCache>>#run global 2/2"),
+        "missing report in stderr: {}",
+        stderr
+    );
+    assert!(
+        !stderr.contains("GlobalCache>>#unused"),
+        "unexpected report in stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn strict_globals_reports_unknown_global() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    fs::write(
+        directory.join("StrictHelper.som"),
+        "StrictHelper = ( ---- greet = ( ^ 'helper' ) )",
+    )
+    .expect("could not write test class");
+    let file = directory.join("GlobalTypo.som");
+    fs::write(
+        &file,
+        "GlobalTypo = ( run = ( StrictHelper greet println. Strng println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .arg("--strict-globals")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("helper\n"),
+        "unexpected stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("ERROR: the global 'Strng' is not defined (strict globals are enabled)"),
+        "missing error in stdout: {}",
+        stdout
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        !stdout.contains("strict globals"),
+        "unexpected error in stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn failed_assertion_reports_error() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("FailedAssertion.som");
+    fs::write(
+        &file,
+        "FailedAssertion = ( run = ( self assert: 1 < 2. self assert: 1 = 2 description: 'one is not two'. 'unreachable' println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("ERROR: assertion failed: one is not two"),
+        "missing error in stdout: {}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("unreachable"),
+        "unexpected stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn partition_with_non_boolean_block_reports_error() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("NonBooleanPartition.som");
+    fs::write(
+        &file,
+        "NonBooleanPartition = ( run = ( #(1 2) partition: [ :e | e ]. 'unreachable' println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("ERROR: the block given to partition: did not return a boolean"),
+        "missing error in stdout: {}",
+        stdout
+    );
+    assert!(
+        !stdout.contains("unreachable"),
+        "unexpected stdout: {}",
+        stdout
+    );
+}
+
+#[test]
+fn rebinding_well_known_global_reports_error() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("RebindGlobal.som");
+    fs::write(
+        &file,
+        "RebindGlobal = ( run = ( Integer println. system global: #Integer put: 42. Integer println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.starts_with("Integer\n"),
+        "unexpected stdout: {}",
+        stdout
+    );
+    assert!(
+        stdout.contains("ERROR: cannot rebind the well-known global 'Integer'"),
+        "missing error in stdout: {}",
+        stdout
+    );
+    assert!(!stdout.contains("42"), "unexpected stdout: {}", stdout);
+
+    fs::write(
+        &file,
+        "RebindGlobal = ( run = ( Smalltalk println. Smalltalk at: #Smalltalk put: 42. Smalltalk println ) )",
+    )
+    .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("ERROR: cannot rebind the well-known global 'Smalltalk'"),
+        "missing error in stdout: {}",
+        stdout
+    );
+    assert!(!stdout.contains("42"), "unexpected stdout: {}", stdout);
+}
+
+#[test]
+fn print_nl_prints_receiver() {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-cli-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join("PrintNl.som");
+    fs::write(&file, "PrintNl = ( run = ( (3 printNl + 1) println ) )")
+        .expect("could not write test class");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_som-interpreter-bc"))
+        .arg(&file)
+        .arg("-c")
+        .arg("../core-lib/Smalltalk")
+        .output()
+        .expect("could not run interpreter");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n4\n");
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "coverage")]
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use som_interpreter_bc::compiler;
+use som_interpreter_bc::frame::FrameKind;
+use som_interpreter_bc::instance::Instance;
+use som_interpreter_bc::interpreter::Interpreter;
+use som_interpreter_bc::universe::Universe;
+use som_interpreter_bc::value::Value;
+use som_lexer::{Lexer, Token};
+use som_parser::lang;
+
+fn setup_universe() -> Universe {
+    let classpath = vec![PathBuf::from("../core-lib/Smalltalk")];
+    Universe::with_classpath(classpath).expect("could not setup test universe")
+}
+
+#[test]
+fn method_invocation_count() {
+    let mut universe = setup_universe();
+
+    let source = "CoverageTest = ( run = ( ^ self helper + self helper ) helper = ( ^ 21 ) unused = ( ^ 0 ) )";
+    let mut lexer = Lexer::new(source).skip_comments(true).skip_whitespace(true);
+    let tokens: Vec<Token> = lexer.by_ref().collect();
+    assert!(
+        lexer.text().is_empty(),
+        "could not fully tokenize test class"
+    );
+
+    let class_def = som_parser::apply(lang::class_def(), tokens.as_slice()).unwrap();
+
+    let object_class = universe.object_class();
+    let class = compiler::compile_class(&mut universe.interner, &class_def, Some(&object_class))
+        .expect("could not compile test class");
+
+    let metaclass_class = universe.metaclass_class();
+    class.borrow_mut().set_super_class(&object_class);
+    class
+        .borrow()
+        .class()
+        .borrow_mut()
+        .set_super_class(&object_class.borrow().class());
+    class
+        .borrow()
+        .class()
+        .borrow_mut()
+        .set_class(&metaclass_class);
+
+    let method_name = universe.intern_symbol("run");
+    let method = class
+        .borrow()
+        .lookup_method(method_name)
+        .expect("method not found ??");
+    let instance = Value::Instance(Rc::new(RefCell::new(Instance::from_class(class.clone()))));
+    let kind = FrameKind::Method {
+        method,
+        holder: class,
+        self_value: instance.clone(),
+    };
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(kind);
+    frame.borrow_mut().args.push(instance);
+    let output = interpreter.run(&mut universe);
+    assert_eq!(output, Some(Value::Integer(42)));
+
+    let holder = universe.intern_symbol("CoverageTest");
+    let helper = universe.intern_symbol("helper");
+    let unused = universe.intern_symbol("unused");
+    assert_eq!(
+        universe.method_invocation_count.get(&(holder, helper)),
+        Some(&2)
+    );
+    assert_eq!(
+        universe.method_invocation_count.get(&(holder, unused)),
+        None
+    );
+
+    let mut dump = Vec::new();
+    universe
+        .dump_method_invocation_count(&mut dump)
+        .expect("could not dump invocation counts");
+    let dump = String::from_utf8(dump).expect("invalid UTF-8 in dump");
+    assert!(dump.lines().any(|line| line == "CoverageTest>>#helper 2"));
+}
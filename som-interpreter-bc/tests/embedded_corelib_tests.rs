@@ -0,0 +1,42 @@
+#![cfg(feature = "embedded-corelib")]
+
+use std::fs;
+
+use som_interpreter_bc::frame::FrameKind;
+use som_interpreter_bc::interpreter::Interpreter;
+use som_interpreter_bc::universe::Universe;
+use som_interpreter_bc::value::Value;
+
+#[test]
+fn runs_without_corelib_on_disk() {
+    let dir = std::env::temp_dir().join("som-interpreter-bc-embedded-corelib-tests");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("Main.som");
+    fs::write(
+        &path,
+        "Main = ( ---- run = ( ^ (1 to: 6) inject: 0 into: [ :a :b | a + b ] ) )",
+    )
+    .unwrap();
+
+    let mut universe = Universe::with_classpath(vec![]).expect("could not setup test universe");
+    let class = universe
+        .load_class_from_path(&path)
+        .expect("could not load test class");
+
+    let receiver = Value::Class(class);
+    let signature = universe.intern_symbol("run");
+    let method = receiver
+        .lookup_method(&universe, signature)
+        .expect("could not find 'Main class>>#run'");
+    let holder = method.holder.upgrade().unwrap();
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(FrameKind::Method {
+        method,
+        holder,
+        self_value: receiver.clone(),
+    });
+    frame.borrow_mut().args.push(receiver);
+
+    assert_eq!(interpreter.run(&mut universe), Some(Value::Integer(21)));
+}
@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use som_interpreter_bc::interner::Interner;
+use som_interpreter_bc::universe::Universe;
+
+#[test]
+fn interner_round_trip() {
+    let mut interner = Interner::with_capacity(16);
+    let names = ["run", "value:", "println", "", "ünïcödé", "at:put:"];
+    let ids: Vec<_> = names.iter().map(|name| interner.intern(name)).collect();
+
+    let mut buffer = Vec::new();
+    interner
+        .save_to(&mut buffer)
+        .expect("could not save interner");
+
+    let mut loaded = Interner::load_from(buffer.as_slice()).expect("could not load interner");
+    for (name, id) in names.iter().zip(ids) {
+        assert_eq!(loaded.lookup(id), *name);
+        assert_eq!(loaded.intern(name), id);
+    }
+
+    let fresh = loaded.intern("fresh");
+    assert_eq!(interner.intern("fresh"), fresh);
+}
+
+#[test]
+fn interner_load_truncated() {
+    let mut interner = Interner::with_capacity(16);
+    interner.intern("truncated");
+
+    let mut buffer = Vec::new();
+    interner
+        .save_to(&mut buffer)
+        .expect("could not save interner");
+    buffer.pop();
+
+    assert!(Interner::load_from(buffer.as_slice()).is_err());
+}
+
+#[test]
+fn interner_load_oversized_lengths() {
+    let mut buffer = Vec::new();
+    buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+    buffer.extend_from_slice(&u32::MAX.to_le_bytes());
+    buffer.extend_from_slice(b"short");
+
+    assert!(Interner::load_from(buffer.as_slice()).is_err());
+}
+
+#[test]
+fn interner_intern_all() {
+    let names = ["run", "value:", "println", "run", "", "ünïcödé"];
+
+    let mut individual = Interner::with_capacity(16);
+    individual.intern("existing");
+    let expected: Vec<_> = names.iter().map(|name| individual.intern(name)).collect();
+
+    let mut batched = Interner::with_capacity(16);
+    batched.intern("existing");
+    assert_eq!(batched.intern_all(names.iter()), expected);
+    assert_eq!(expected[0], expected[3]);
+
+    let mut universe = Universe::with_classpath(vec![PathBuf::from("../core-lib/Smalltalk")])
+        .expect("could not setup test universe");
+    let symbols = universe.intern_symbols(&names);
+    for (name, symbol) in names.iter().zip(symbols) {
+        assert_eq!(universe.intern_symbol(name), symbol);
+        assert_eq!(universe.lookup_symbol(symbol), *name);
+    }
+}
@@ -0,0 +1,278 @@
+use std::cell::{OnceCell, RefCell};
+use std::path::PathBuf;
+use std::rc::{Rc, Weak};
+
+use indexmap::IndexMap;
+use som_core::bytecode::Bytecode;
+use som_interpreter_bc::class::{Class, MaybeWeak};
+use som_interpreter_bc::compiler::Literal;
+use som_interpreter_bc::frame::FrameKind;
+use som_interpreter_bc::instance::Instance;
+use som_interpreter_bc::interpreter::Interpreter;
+use som_interpreter_bc::method::{Method, MethodEnv, MethodKind};
+use som_interpreter_bc::universe::Universe;
+use som_interpreter_bc::value::Value;
+
+fn setup_universe() -> Universe {
+    let classpath = vec![PathBuf::from("../core-lib/Smalltalk")];
+    Universe::with_classpath(classpath).expect("could not setup test universe")
+}
+
+#[test]
+fn halt_stops_run_loop() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    let method = Rc::new(Method {
+        kind: MethodKind::Defined(MethodEnv {
+            locals: vec![],
+            literals: vec![Literal::Integer(7)],
+            body: vec![
+                Bytecode::PushConstant(0),
+                Bytecode::Halt,
+                Bytecode::ReturnLocal,
+            ],
+            global_cache: Rc::new(vec![OnceCell::new()]),
+            #[cfg(feature = "stack-maps")]
+            stack_map: vec![],
+        }),
+        holder: Rc::downgrade(&object_class),
+        signature: String::from("halting"),
+    });
+    let kind = FrameKind::Method {
+        method,
+        holder: object_class,
+        self_value: Value::Nil,
+    };
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(kind);
+    frame.borrow_mut().args.push(Value::Nil);
+
+    assert_eq!(interpreter.run(&mut universe), Some(Value::Nil));
+    assert_eq!(frame.borrow().bytecode_idx, 2);
+    assert_eq!(frame.borrow().stack, vec![Value::Integer(7)]);
+
+    assert_eq!(interpreter.run(&mut universe), Some(Value::Integer(7)));
+    assert!(interpreter.current_frame().is_none());
+}
+
+#[test]
+fn cached_global_matches_uncached_global() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    let array = universe.intern_symbol("Array");
+    let array_class = Value::Class(universe.array_class());
+    let make_method = |push: Bytecode| {
+        Rc::new(Method {
+            kind: MethodKind::Defined(MethodEnv {
+                locals: vec![],
+                literals: vec![Literal::Symbol(array)],
+                body: vec![push, Bytecode::ReturnLocal],
+                global_cache: Rc::new(vec![OnceCell::new()]),
+                #[cfg(feature = "stack-maps")]
+                stack_map: vec![],
+            }),
+            holder: Rc::downgrade(&object_class),
+            signature: String::from("pushArray"),
+        })
+    };
+    let mut run = |method: &Rc<Method>| {
+        let mut interpreter = Interpreter::new();
+        let frame = interpreter.push_frame(FrameKind::Method {
+            method: method.clone(),
+            holder: object_class.clone(),
+            self_value: Value::Nil,
+        });
+        frame.borrow_mut().args.push(Value::Nil);
+        interpreter.run(&mut universe)
+    };
+
+    let uncached = make_method(Bytecode::PushGlobal(0));
+    let cached = make_method(Bytecode::PushGlobalCached(0));
+
+    let expected = run(&uncached);
+    assert_eq!(expected, Some(array_class.clone()));
+    assert_eq!(run(&cached), expected);
+
+    match cached.kind() {
+        MethodKind::Defined(env) => {
+            assert_eq!(env.global_cache[0].get().cloned(), Some(array_class))
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(run(&cached), expected);
+}
+
+#[test]
+fn missing_does_not_understand_reports_error() {
+    let mut universe = setup_universe();
+
+    // A class outside of the usual hierarchy, which does not inherit `Object>>#doesNotUnderstand:arguments:`,
+    // and whose `error:` answers the message instead of exiting.
+    let bare_class = Rc::new(RefCell::new(Class {
+        name: String::from("Bare"),
+        class: MaybeWeak::Weak(Weak::new()),
+        super_class: Weak::new(),
+        locals: IndexMap::new(),
+        methods: IndexMap::new(),
+        is_static: false,
+        comment: None,
+    }));
+    let error = Rc::new(Method {
+        kind: MethodKind::Defined(MethodEnv {
+            locals: vec![],
+            literals: vec![],
+            body: vec![Bytecode::PushArgument(0, 1), Bytecode::ReturnLocal],
+            global_cache: Rc::new(vec![]),
+            #[cfg(feature = "stack-maps")]
+            stack_map: vec![],
+        }),
+        holder: Rc::downgrade(&bare_class),
+        signature: String::from("error:"),
+    });
+    let error_sym = universe.intern_symbol("error:");
+    bare_class.borrow_mut().methods.insert(error_sym, error);
+    let instance = Value::Instance(Rc::new(RefCell::new(Instance::from_class(
+        bare_class.clone(),
+    ))));
+
+    let object_class = universe.object_class();
+    let foo = universe.intern_symbol("foo");
+    let method = Rc::new(Method {
+        kind: MethodKind::Defined(MethodEnv {
+            locals: vec![],
+            literals: vec![Literal::Symbol(foo)],
+            body: vec![
+                Bytecode::PushArgument(0, 1),
+                Bytecode::Send(0),
+                Bytecode::ReturnLocal,
+            ],
+            global_cache: Rc::new(vec![OnceCell::new()]),
+            #[cfg(feature = "stack-maps")]
+            stack_map: vec![],
+        }),
+        holder: Rc::downgrade(&object_class),
+        signature: String::from("sendFoo:"),
+    });
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(FrameKind::Method {
+        method,
+        holder: object_class,
+        self_value: Value::Nil,
+    });
+    frame.borrow_mut().args.push(Value::Nil);
+    frame.borrow_mut().args.push(instance);
+
+    assert_eq!(
+        interpreter.run(&mut universe),
+        Some(Value::String(Rc::new(String::from(
+            "could not find method 'Bare>>#foo', and its receiver does not define 'doesNotUnderstand:arguments:'"
+        ))))
+    );
+}
+
+#[test]
+fn send_after_redefinition_hits_new_method() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    let answer = universe.intern_symbol("answer");
+    let make_method = |literals: Vec<Literal>, body: Vec<Bytecode>, signature: &str| {
+        Rc::new(Method {
+            kind: MethodKind::Defined(MethodEnv {
+                locals: vec![],
+                global_cache: Rc::new(literals.iter().map(|_| OnceCell::new()).collect()),
+                literals,
+                body,
+                #[cfg(feature = "stack-maps")]
+                stack_map: vec![],
+            }),
+            holder: Rc::downgrade(&object_class),
+            signature: String::from(signature),
+        })
+    };
+    let make_answer = |value: i64| {
+        make_method(
+            vec![Literal::Integer(value)],
+            vec![Bytecode::PushConstant(0), Bytecode::ReturnLocal],
+            "answer",
+        )
+    };
+
+    let caller = make_method(
+        vec![Literal::Symbol(answer)],
+        vec![
+            Bytecode::PushArgument(0, 0),
+            Bytecode::Send(0),
+            Bytecode::ReturnLocal,
+        ],
+        "callAnswer",
+    );
+    let run = |universe: &mut Universe| {
+        let mut interpreter = Interpreter::new();
+        let frame = interpreter.push_frame(FrameKind::Method {
+            method: caller.clone(),
+            holder: object_class.clone(),
+            self_value: Value::Nil,
+        });
+        frame.borrow_mut().args.push(Value::Nil);
+        interpreter.run(universe)
+    };
+
+    let first = make_answer(1);
+    object_class.borrow_mut().methods.insert(answer, first);
+    assert_eq!(run(&mut universe), Some(Value::Integer(1)));
+
+    let second = make_answer(2);
+    object_class.borrow_mut().methods.insert(answer, second);
+    assert_eq!(run(&mut universe), Some(Value::Integer(2)));
+}
+
+#[test]
+fn nil_local_and_pop2() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    let method = Rc::new(Method {
+        kind: MethodKind::Defined(MethodEnv {
+            locals: vec![Value::Nil],
+            literals: vec![Literal::Integer(7), Literal::Integer(42)],
+            body: vec![
+                Bytecode::PushConstant(0),
+                Bytecode::PopLocal(0, 0),
+                Bytecode::NilLocal(0),
+                Bytecode::PushLocal(0, 0),
+                Bytecode::PushConstant(0),
+                Bytecode::PushConstant(0),
+                Bytecode::Pop2,
+                Bytecode::Halt,
+                Bytecode::Pop,
+                Bytecode::PushConstant(1),
+                Bytecode::ReturnLocal,
+            ],
+            global_cache: Rc::new(vec![OnceCell::new(), OnceCell::new()]),
+            #[cfg(feature = "stack-maps")]
+            stack_map: vec![],
+        }),
+        holder: Rc::downgrade(&object_class),
+        signature: String::from("resetting"),
+    });
+    let kind = FrameKind::Method {
+        method,
+        holder: object_class,
+        self_value: Value::Nil,
+    };
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(kind);
+    frame.borrow_mut().args.push(Value::Nil);
+
+    assert_eq!(interpreter.run(&mut universe), Some(Value::Nil));
+    assert_eq!(frame.borrow().lookup_local(0), Some(Value::Nil));
+    assert_eq!(frame.borrow().stack, vec![Value::Nil]);
+
+    assert_eq!(interpreter.run(&mut universe), Some(Value::Integer(42)));
+}
@@ -0,0 +1,1623 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use num_bigint::BigInt;
+use som_interpreter_bc::class::Class;
+use som_interpreter_bc::compiler;
+use som_interpreter_bc::frame::FrameKind;
+use som_interpreter_bc::interpreter::Interpreter;
+use som_interpreter_bc::universe::Universe;
+use som_interpreter_bc::value::Value;
+use som_interpreter_bc::SOMRef;
+use som_lexer::{Lexer, Token};
+
+fn setup_universe() -> Universe {
+    let classpath = vec![PathBuf::from("../core-lib/Smalltalk")];
+    Universe::with_classpath(classpath).expect("could not setup test universe")
+}
+
+fn load_class(universe: &mut Universe, source: &str, super_class: &SOMRef<Class>) -> SOMRef<Class> {
+    let mut lexer = Lexer::new(source)
+        .skip_comments(false)
+        .skip_whitespace(true);
+    let tokens: Vec<Token> = lexer.by_ref().collect();
+    assert!(
+        lexer.text().is_empty(),
+        "could not fully tokenize test class"
+    );
+
+    let class_def = som_parser::parse_file(tokens.as_slice()).unwrap();
+
+    let class = compiler::compile_class(&mut universe.interner, &class_def, Some(super_class))
+        .expect("could not compile test class");
+
+    let metaclass_class = universe.metaclass_class();
+    class.borrow_mut().set_super_class(super_class);
+    class
+        .borrow()
+        .class()
+        .borrow_mut()
+        .set_super_class(&super_class.borrow().class());
+    class
+        .borrow()
+        .class()
+        .borrow_mut()
+        .set_class(&metaclass_class);
+
+    let name = universe.intern_symbol(class_def.name.as_str());
+    universe.assign_global(name, Value::Class(class.clone()));
+
+    class
+}
+
+fn evaluate(universe: &mut Universe, expr: &str) -> Value {
+    evaluate_method(universe, &format!("^ ( {} )", expr))
+}
+
+fn evaluate_method(universe: &mut Universe, body: &str) -> Value {
+    let object_class = universe.object_class();
+    let class = load_class(
+        universe,
+        &format!("PrimitiveTest = ( run = ( {} ) )", body),
+        &object_class,
+    );
+
+    let method_name = universe.intern_symbol("run");
+    let method = class
+        .borrow()
+        .lookup_method(method_name)
+        .expect("method not found ??");
+    let kind = FrameKind::Method {
+        method,
+        holder: class.clone(),
+        self_value: Value::Class(class.clone()),
+    };
+
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(kind);
+    frame.borrow_mut().args.push(Value::Class(class));
+    interpreter
+        .run(universe)
+        .expect("test method did not produce a value")
+}
+
+#[test]
+fn print_nl_returns_receiver() {
+    let mut universe = setup_universe();
+
+    assert_eq!(evaluate(&mut universe, "3 printNl"), Value::Integer(3));
+    assert_eq!(evaluate(&mut universe, "3 printNl + 1"), Value::Integer(4));
+}
+
+#[test]
+fn array_collect() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "(#(1 2 3) collect: [ :e | e * 2 ]) length"),
+        Value::Integer(3)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(#(1 2 3) collect: [ :e | e * 2 ]) at: 3"),
+        Value::Integer(6)
+    );
+
+    let early = universe.intern_symbol("early");
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "#(1 2 3) collect: [ :e | e = 2 ifTrue: [ ^ #early ]. e ]. ^ #late"
+        ),
+        Value::Symbol(early)
+    );
+}
+
+#[test]
+fn string_as_number() {
+    let mut universe = setup_universe();
+
+    assert_eq!(evaluate(&mut universe, "'42' asNumber"), Value::Integer(42));
+    assert_eq!(
+        evaluate(&mut universe, "' -7 ' asNumber"),
+        Value::Integer(-7)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'2.5' asNumber"),
+        Value::Double(2.5)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'1e3' asNumber"),
+        Value::Double(1000.0)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'123456789012345678901234567890' asNumber"),
+        Value::BigInteger("123456789012345678901234567890".parse().unwrap())
+    );
+    assert_eq!(evaluate(&mut universe, "'oops' asNumber"), Value::Nil);
+    assert_eq!(evaluate(&mut universe, "'' asNumber"), Value::Nil);
+}
+
+#[test]
+fn integer_as_string() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "-123 asString"),
+        Value::String(Rc::new(String::from("-123")))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "-123 asString = '-123'"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "123456789012345678901234567890 asString"),
+        Value::String(Rc::new(String::from("123456789012345678901234567890")))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "-123456789012345678901234567890 asString"),
+        Value::String(Rc::new(String::from("-123456789012345678901234567890")))
+    );
+}
+
+#[test]
+fn class_all_inst_var_names() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    let parent = load_class(&mut universe, "InstVarParent = ( | a b | )", &object_class);
+    load_class(
+        &mut universe,
+        "InstVarChild = InstVarParent ( | c | )",
+        &parent,
+    );
+
+    let names: Vec<_> = ["a", "b", "c"]
+        .iter()
+        .map(|name| Value::Symbol(universe.intern_symbol(name)))
+        .collect();
+    assert_eq!(
+        evaluate(&mut universe, "InstVarChild allInstVarNames"),
+        Value::Array(Rc::new(RefCell::new(names)))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "InstVarChild allInstVarNames length"),
+        Value::Integer(3)
+    );
+}
+
+#[test]
+fn class_comment() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    load_class(
+        &mut universe,
+        "Commented = ( \"A documented class.\" foo = ( \"not the class comment\" ^ 1 ) )",
+        &object_class,
+    );
+    load_class(&mut universe, "Uncommented = ( )", &object_class);
+
+    assert_eq!(
+        evaluate(&mut universe, "Commented comment"),
+        Value::String(Rc::new(String::from("A documented class.")))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "Uncommented comment"),
+        Value::String(Rc::new(String::new()))
+    );
+}
+
+#[test]
+fn object_responds_to() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "3 respondsTo: #+"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "3 respondsTo: #printNl"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "3 respondsTo: #nope"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'abc' respondsTo: #length"),
+        Value::Boolean(true)
+    );
+
+    // The core library's definition would ask `self class`, which this class lies about.
+    let object_class = universe.object_class();
+    load_class(
+        &mut universe,
+        "Impostor = ( class = ( ^Object ) frob = ( ^1 ) )",
+        &object_class,
+    );
+    assert_eq!(
+        evaluate(&mut universe, "Impostor new respondsTo: #frob"),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn double_special_values() {
+    let mut universe = setup_universe();
+
+    let cases = [
+        ("1.5", false, false, true),
+        ("Double PositiveInfinity", false, true, false),
+        ("(0.0 - Double PositiveInfinity)", false, true, false),
+        (
+            "(Double PositiveInfinity - Double PositiveInfinity)",
+            true,
+            false,
+            false,
+        ),
+    ];
+    for (expr, is_nan, is_infinite, is_finite) in cases.iter() {
+        assert_eq!(
+            evaluate(&mut universe, &format!("{} isNaN", expr)),
+            Value::Boolean(*is_nan),
+            "unexpected 'isNaN' for '{}'",
+            expr
+        );
+        assert_eq!(
+            evaluate(&mut universe, &format!("{} isInfinite", expr)),
+            Value::Boolean(*is_infinite),
+            "unexpected 'isInfinite' for '{}'",
+            expr
+        );
+        assert_eq!(
+            evaluate(&mut universe, &format!("{} isFinite", expr)),
+            Value::Boolean(*is_finite),
+            "unexpected 'isFinite' for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn system_current_time_millis() {
+    let mut universe = setup_universe();
+
+    // 2020-01-01T00:00:00Z, as a lower bound.
+    assert_eq!(
+        evaluate(&mut universe, "system currentTimeMillis > 1577836800000"),
+        Value::Boolean(true)
+    );
+    match evaluate(&mut universe, "system currentTimeMillis") {
+        Value::Integer(millis) => assert!(millis > 1_577_836_800_000),
+        value => panic!("unexpected value: {:?}", value),
+    }
+}
+
+#[test]
+fn object_default_equality_is_identity() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    load_class(&mut universe, "EqualityTest = ( | x | )", &object_class);
+
+    assert_eq!(
+        evaluate(&mut universe, "EqualityTest new = EqualityTest new"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "| a | a := EqualityTest new. ^ a = a"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(Array new: 1) == (Array new: 1)"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "| a | a := Array new: 1. ^ a == a"),
+        Value::Boolean(true)
+    );
+    assert_eq!(evaluate(&mut universe, "3 = 3"), Value::Boolean(true));
+    assert_eq!(
+        evaluate(&mut universe, "'abc' = 'abc'"),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn integer_to() {
+    let mut universe = setup_universe();
+
+    let range = (1..=5).map(Value::Integer).collect();
+    assert_eq!(
+        evaluate(&mut universe, "1 to: 5"),
+        Value::Array(Rc::new(RefCell::new(range)))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(-2 to: -2) length"),
+        Value::Integer(1)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(5 to: 1) length"),
+        Value::Integer(0)
+    );
+    assert_eq!(evaluate(&mut universe, "0 to: 100000000"), Value::Nil);
+    assert_eq!(
+        evaluate(&mut universe, "0 to: 123456789012345678901234567890"),
+        Value::Nil
+    );
+}
+
+#[test]
+fn array_do_non_local_return() {
+    let mut universe = setup_universe();
+
+    let early = universe.intern_symbol("early");
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "#(1 2 3) do: [ :e | e = 2 ifTrue: [ ^ #early ] ]. ^ #late"
+        ),
+        Value::Symbol(early)
+    );
+}
+
+#[test]
+fn array_do_with_index() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| sum | sum := 0. #(10 20 30) doWithIndex: [ :e :i | sum := sum + (i * e) ]. ^ sum"
+        ),
+        Value::Integer(140)
+    );
+
+    let early = universe.intern_symbol("early");
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "#(1 2 3) doWithIndex: [ :e :i | i = 2 ifTrue: [ ^ #early ] ]. ^ #late"
+        ),
+        Value::Symbol(early)
+    );
+}
+
+#[test]
+fn array_new_is_filled_with_nil() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "Array new: 1000"),
+        Value::Array(Rc::new(RefCell::new(vec![Value::Nil; 1000])))
+    );
+}
+
+#[test]
+fn string_reverse() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "'abc' reverse"),
+        Value::String(Rc::new(String::from("cba")))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'' reverse"),
+        Value::String(Rc::new(String::new()))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'héllo wörld ✓' reverse"),
+        Value::String(Rc::new(String::from("✓ dlröw olléh")))
+    );
+}
+
+#[test]
+fn array_integral_double_index() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "#(10 20 30) at: 2.0"),
+        Value::Integer(20)
+    );
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| a | a := Array new: 3. a at: 3.0 put: 42. ^ a at: 3"
+        ),
+        Value::Integer(42)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' primSubstringFrom: 2.0 to: 4.0"),
+        Value::String(Rc::new(String::from("ell")))
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid index")]
+fn array_non_integral_double_index() {
+    let mut universe = setup_universe();
+
+    evaluate(&mut universe, "#(10 20 30) at: 2.5");
+}
+
+#[test]
+fn boolean_short_circuit() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| evaluated | evaluated := false. false and: [ evaluated := true ]. true or: [ evaluated := true ]. ^ evaluated",
+        ),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "true and: [ 42 ]"),
+        Value::Integer(42)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "false or: [ 42 ]"),
+        Value::Integer(42)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "true & false"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "false | true"),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn array_first_last() {
+    let mut universe = setup_universe();
+
+    let array = |values: &[i64]| {
+        Value::Array(Rc::new(RefCell::new(
+            values.iter().copied().map(Value::Integer).collect(),
+        )))
+    };
+
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3 4) first: 2"),
+        array(&[1, 2])
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3 4) last: 2"),
+        array(&[3, 4])
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3) first: 100"),
+        array(&[1, 2, 3])
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3) last: 100"),
+        array(&[1, 2, 3])
+    );
+    assert_eq!(evaluate(&mut universe, "#(1 2 3) first: -1"), array(&[]));
+    assert_eq!(evaluate(&mut universe, "#(1 2 3) last: -1"), array(&[]));
+}
+
+#[test]
+fn system_environment_variable() {
+    let mut universe = setup_universe();
+
+    std::env::set_var("SOM_BC_PRIMITIVE_TEST_VAR", "hello from the environment");
+    std::env::remove_var("SOM_BC_PRIMITIVE_TEST_UNSET");
+    som_interpreter_bc::primitives::set_allow_environment(true);
+
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "system environmentVariable: 'SOM_BC_PRIMITIVE_TEST_VAR'"
+        ),
+        Value::String(Rc::new(String::from("hello from the environment")))
+    );
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "system environmentVariable: 'SOM_BC_PRIMITIVE_TEST_UNSET'"
+        ),
+        Value::Nil
+    );
+}
+
+#[test]
+fn string_print_string() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    assert_eq!(evaluate(&mut universe, "'hi' printString"), string("'hi'"));
+    assert_eq!(evaluate(&mut universe, "'hi' asString"), string("hi"));
+    assert_eq!(
+        evaluate(&mut universe, r"'it\'s' printString"),
+        string(r"'it\'s'")
+    );
+    assert_eq!(
+        evaluate(&mut universe, r"'a\\' printString"),
+        string(r"'a\\'")
+    );
+    assert_eq!(evaluate(&mut universe, "#foo printString"), string("#foo"));
+    assert_eq!(evaluate(&mut universe, "#foo asString"), string("foo"));
+    assert_eq!(evaluate(&mut universe, "42 printString"), string("42"));
+}
+
+#[test]
+fn string_occurrences_of() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "'ababa' occurrencesOf: 'aba'"),
+        Value::Integer(1)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'abcabcabc' occurrencesOf: 'bc'"),
+        Value::Integer(3)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'abc' occurrencesOf: 'x'"),
+        Value::Integer(0)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'abc' occurrencesOf: ''"),
+        Value::Integer(0)
+    );
+}
+
+#[test]
+fn integer_radix_strings() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    assert_eq!(evaluate(&mut universe, "0 asString: 2"), string("0"));
+    assert_eq!(evaluate(&mut universe, "-10 asString: 2"), string("-1010"));
+    assert_eq!(evaluate(&mut universe, "255 printString: 16"), string("ff"));
+    assert_eq!(evaluate(&mut universe, "0 printString: 16"), string("0"));
+    assert_eq!(
+        evaluate(&mut universe, "-10 printString: 2"),
+        string("-1010")
+    );
+}
+
+#[test]
+fn array_occurrences_of() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 1 3 1) occurrencesOf: 1"),
+        Value::Integer(3)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 #foo 'bar' #foo) occurrencesOf: #foo"),
+        Value::Integer(2)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3) occurrencesOf: 4"),
+        Value::Integer(0)
+    );
+}
+
+#[test]
+fn double_as_string_with_decimals() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    assert_eq!(
+        evaluate(&mut universe, "3.14159 asStringWithDecimals: 2"),
+        string("3.14")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "2.5 asStringWithDecimals: 3"),
+        string("2.500")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "3.7 asStringWithDecimals: -1"),
+        string("4")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(0.0 // 0.0) asStringWithDecimals: 2"),
+        string("nan")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(1.0 // 0.0) asStringWithDecimals: 2"),
+        string("inf")
+    );
+    for decimals in ["1074", "70000", "1000000000000000"].iter() {
+        assert_eq!(
+            evaluate(
+                &mut universe,
+                &format!("1.5 asStringWithDecimals: {}", decimals)
+            ),
+            string(&format!("1.5{}", "0".repeat(1073)))
+        );
+    }
+}
+
+#[test]
+fn object_nil_checks() {
+    let mut universe = setup_universe();
+
+    assert_eq!(evaluate(&mut universe, "nil isNil"), Value::Boolean(true));
+    assert_eq!(evaluate(&mut universe, "nil notNil"), Value::Boolean(false));
+    assert_eq!(evaluate(&mut universe, "3 isNil"), Value::Boolean(false));
+    assert_eq!(evaluate(&mut universe, "3 notNil"), Value::Boolean(true));
+
+    let object_class = universe.object_class();
+    for selector in ["isNil", "notNil"].iter() {
+        let signature = universe.intern_symbol(selector);
+        let method = object_class
+            .borrow()
+            .lookup_method(signature)
+            .expect("method not found");
+        assert!(
+            method.is_primitive(),
+            "Object>>#{} is not bound to its primitive",
+            selector
+        );
+    }
+}
+
+#[test]
+fn array_all_any_satisfy() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("#(2 4 6) allSatisfy: [ :x | x % 2 = 0 ]", true),
+        ("#(2 3 6) allSatisfy: [ :x | x % 2 = 0 ]", false),
+        ("#(1 3 4) anySatisfy: [ :x | x % 2 = 0 ]", true),
+        ("#(1 3 5) anySatisfy: [ :x | x % 2 = 0 ]", false),
+        ("#() allSatisfy: [ :x | false ]", true),
+        ("#() anySatisfy: [ :x | true ]", false),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::Boolean(*expected),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+
+    for (expr, expected) in [
+        ("#(1 2 3) anySatisfy: [ :x | count := count + 1. x = 1 ]", 1),
+        ("#(1 2 3) allSatisfy: [ :x | count := count + 1. x > 1 ]", 1),
+    ]
+    .iter()
+    {
+        assert_eq!(
+            evaluate_method(
+                &mut universe,
+                &format!("| count | count := 0. {}. ^ count", expr)
+            ),
+            Value::Integer(*expected),
+            "for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn string_index_of_sub_collection() {
+    let mut universe = setup_universe();
+
+    for selector in ["indexOf:", "indexOfSubCollection:"].iter() {
+        let cases = [
+            ("'hello world' {} 'world'", 7),
+            ("'hello world' {} 'o'", 5),
+            ("'hello world' {} 'xyz'", 0),
+            ("'hello' {} ''", 0),
+            ("'héllo wörld' {} 'wörld'", 7),
+        ];
+        for (template, expected) in cases.iter() {
+            let expr = template.replace("{}", selector);
+            assert_eq!(
+                evaluate(&mut universe, &expr),
+                Value::Integer(*expected),
+                "for '{}'",
+                expr
+            );
+        }
+    }
+}
+
+#[test]
+fn number_abs() {
+    let mut universe = setup_universe();
+
+    assert_eq!(evaluate(&mut universe, "-5 abs"), Value::Integer(5));
+    assert_eq!(evaluate(&mut universe, "5 abs"), Value::Integer(5));
+    assert_eq!(
+        evaluate(&mut universe, "(0 - 9223372036854775807 - 1) abs"),
+        Value::BigInteger(BigInt::from(i64::MAX) + 1)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(0 - 9223372036854775808) abs"),
+        Value::BigInteger(BigInt::from(i64::MAX) + 1)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(0 - 9223372036854775807) abs"),
+        Value::Integer(i64::MAX)
+    );
+    assert_eq!(evaluate(&mut universe, "-2.5 abs"), Value::Double(2.5));
+}
+
+#[test]
+fn class_includes_behavior() {
+    let mut universe = setup_universe();
+
+    let cases = [
+        ("Integer includesBehavior: Object", true),
+        ("Object includesBehavior: Integer", false),
+        ("Integer includesBehavior: Integer", true),
+        ("Integer includesBehavior: String", false),
+        ("Integer class includesBehavior: Object class", true),
+        ("Object class includesBehavior: Integer class", false),
+        ("Integer includesBehavior: Integer class", false),
+    ];
+    for (expr, expected) in cases.iter() {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::Boolean(*expected),
+            "for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn array_with_index_collect() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "#(10 20 30) withIndexCollect: [ :e :i | e + i ]"
+        ),
+        Value::Array(Rc::new(RefCell::new(vec![
+            Value::Integer(11),
+            Value::Integer(22),
+            Value::Integer(33),
+        ])))
+    );
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "(#() withIndexCollect: [ :e :i | e ]) length"
+        ),
+        Value::Integer(0)
+    );
+
+    let early = universe.intern_symbol("early");
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "#(1 2 3) withIndexCollect: [ :e :i | i = 2 ifTrue: [ ^ #early ]. e ]. ^ #late"
+        ),
+        Value::Symbol(early)
+    );
+}
+
+#[test]
+fn symbol_equality() {
+    let mut universe = setup_universe();
+
+    let cases = [
+        ("#foo = #foo", true),
+        ("#foo = #bar", false),
+        ("#foo = 'foo'", false),
+        ("#foo = 'foo' asSymbol", true),
+        ("#foo = 3", false),
+        ("#foo = nil", false),
+    ];
+    for (expr, expected) in cases.iter() {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::Boolean(*expected),
+            "for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn string_do() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| result | result := ''. 'héllo' do: [ :ch | result := result concatenate: ch ]. ^ result"
+        ),
+        Value::String(Rc::new(String::from("héllo")))
+    );
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| count | count := 0. 'abc' do: [ :ch | count := count + ch length ]. ^ count"
+        ),
+        Value::Integer(3)
+    );
+
+    let early = universe.intern_symbol("early");
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "'abc' do: [ :ch | ch = 'b' ifTrue: [ ^ #early ] ]. ^ #late"
+        ),
+        Value::Symbol(early)
+    );
+}
+
+#[test]
+fn integer_as_big_integer() {
+    let mut universe = setup_universe();
+
+    let integer_class = Value::Class(universe.integer_class());
+
+    assert_eq!(
+        evaluate(&mut universe, "5 asBigInteger"),
+        Value::BigInteger(BigInt::from(5))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "5 asBigInteger asBigInteger"),
+        Value::BigInteger(BigInt::from(5))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "5 asBigInteger = 5"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "5 = 5 asBigInteger"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "5 asBigInteger class"),
+        integer_class
+    );
+}
+
+#[test]
+fn array_at_if_absent() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("#(1 2 3) at: 2 ifAbsent: [ 42 ]", 2),
+        ("#(1 2 3) at: 4 ifAbsent: [ 42 ]", 42),
+        ("#(1 2 3) at: 0 ifAbsent: [ 42 ]", 42),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::Integer(*expected),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| count | count := 0. #(1 2 3) at: 1 ifAbsent: [ count := count + 1 ]. ^ count"
+        ),
+        Value::Integer(0)
+    );
+}
+
+#[test]
+fn number_negated() {
+    let mut universe = setup_universe();
+
+    assert_eq!(evaluate(&mut universe, "5 negated"), Value::Integer(-5));
+    assert_eq!(
+        evaluate(&mut universe, "5 negated = -5"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "(0 - 9223372036854775807 - 1) negated"),
+        Value::BigInteger(BigInt::from(i64::MAX) + 1)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "9223372036854775808 negated"),
+        Value::Integer(i64::MIN)
+    );
+    assert_eq!(evaluate(&mut universe, "2.5 negated"), Value::Double(-2.5));
+    assert_eq!(
+        evaluate(&mut universe, "2.5 negated = -2.5"),
+        Value::Boolean(true)
+    );
+}
+
+#[test]
+fn array_remove_all() {
+    let mut universe = setup_universe();
+
+    let array = |values: &[i64]| {
+        Value::Array(Rc::new(RefCell::new(
+            values.iter().copied().map(Value::Integer).collect(),
+        )))
+    };
+
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 1 3) removeAll: 1"),
+        array(&[2, 3])
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3) removeAll: 4"),
+        array(&[1, 2, 3])
+    );
+    assert_eq!(evaluate(&mut universe, "#(1 1) removeAll: 1"), array(&[]));
+}
+
+#[test]
+fn integer_print_string_justified() {
+    let mut universe = setup_universe();
+
+    let string = |value: &str| Value::String(Rc::new(String::from(value)));
+
+    assert_eq!(
+        evaluate(&mut universe, "7 printStringRightJustified: 4"),
+        string("   7")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "7 printStringLeftJustified: 4"),
+        string("7   ")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "-42 printStringRightJustified: 5"),
+        string("  -42")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "12345 printStringRightJustified: 2"),
+        string("12345")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "12345 printStringLeftJustified: -1"),
+        string("12345")
+    );
+    assert_eq!(
+        evaluate(&mut universe, "5 printStringRightJustified: 70000"),
+        string(&format!("{}5", " ".repeat(69_999)))
+    );
+}
+
+#[test]
+#[should_panic(expected = "the width exceeds the maximum of 16777216 characters")]
+fn integer_print_string_justified_huge_width() {
+    let mut universe = setup_universe();
+
+    evaluate(
+        &mut universe,
+        "5 printStringRightJustified: 1000000000000000",
+    );
+}
+
+#[test]
+fn string_matches_regex() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: 'h*o'"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: 'h*x'"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: 'h?llo'"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: 'hell'"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: '[a-h]ello'"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: '[!a-h]ello'"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'a*b' matchesRegex: 'a\\\\*b'"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'axb' matchesRegex: 'a\\\\*b'"),
+        Value::Boolean(false)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'' matchesRegex: '*'"),
+        Value::Boolean(true)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: '[a-z'"),
+        Value::Nil
+    );
+    assert_eq!(
+        evaluate(&mut universe, "'hello' matchesRegex: '[z-a]ello'"),
+        Value::Nil
+    );
+}
+
+#[test]
+fn array_replace_from_to_with() {
+    let mut universe = setup_universe();
+
+    let array = |values: &[i64]| {
+        Value::Array(Rc::new(RefCell::new(
+            values.iter().copied().map(Value::Integer).collect(),
+        )))
+    };
+
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "#(1 2 3 4 5) replaceFrom: 2 to: 4 with: #(7 8 9)"
+        ),
+        array(&[1, 7, 8, 9, 5])
+    );
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "#(1 2 3) replaceFrom: 1 to: 1 with: #(7 8 9)"
+        ),
+        array(&[7, 2, 3])
+    );
+    assert_eq!(
+        evaluate(
+            &mut universe,
+            "#(1 2 3) replaceFrom: 2 to: 4 with: #(7 8 9)"
+        ),
+        Value::Nil
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3) replaceFrom: 1 to: 3 with: #(7 8)"),
+        Value::Nil
+    );
+}
+
+#[test]
+fn object_as_string() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    load_class(&mut universe, "Point = ( | x y | )", &object_class);
+    load_class(&mut universe, "Account = ( )", &object_class);
+
+    let tests = &[
+        ("Point new asString", "a Point"),
+        ("Account new asString", "an Account"),
+        ("Object new asString", "an Object"),
+        ("42 asString", "42"),
+        ("nil asString", "nil"),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::String(Rc::new(expected.to_string())),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn universe_load_class_from_source() {
+    let mut universe = setup_universe();
+
+    universe
+        .load_class_from_source("Greeter = ( greet = ( ^'hello, world' ) )")
+        .expect("could not load class from source");
+    universe
+        .load_class_from_source("LoudGreeter = Greeter ( greet = ( ^super greet + '!' ) )")
+        .expect("could not load subclass from source");
+
+    assert_eq!(
+        evaluate(&mut universe, "Greeter new greet"),
+        Value::String(Rc::new(String::from("hello, world")))
+    );
+    assert_eq!(
+        evaluate(&mut universe, "LoudGreeter new greet"),
+        Value::String(Rc::new(String::from("hello, world!")))
+    );
+
+    let err = universe
+        .load_class_from_source("Orphan = MissingParent ( )")
+        .expect_err("loaded a class with a missing superclass");
+    assert_eq!(
+        err.to_string(),
+        "could not find the 'MissingParent' class (superclass of 'Orphan')"
+    );
+    assert!(universe.load_class_from_source("Broken = (").is_err());
+}
+
+#[test]
+fn double_inverse_trigonometry() {
+    let mut universe = setup_universe();
+
+    let mut double = |expr: &str| match evaluate(&mut universe, expr) {
+        Value::Double(value) => value,
+        value => panic!("unexpected value for '{}': {:?}", expr, value),
+    };
+
+    let tests = &[
+        ("1.0 arcTan: 1.0", std::f64::consts::FRAC_PI_4),
+        ("1.0 arcTan: -1.0", 3.0 * std::f64::consts::FRAC_PI_4),
+        ("1.0 arcTan", std::f64::consts::FRAC_PI_4),
+        ("1.0 arcSin", std::f64::consts::FRAC_PI_2),
+        ("1.0 arcCos", 0.0),
+        ("0.5 arcCos", std::f64::consts::FRAC_PI_3),
+    ];
+
+    for (expr, expected) in tests {
+        let value = double(expr);
+        assert!(
+            (value - expected).abs() < 1e-12,
+            "unexpected result for '{}': {}",
+            expr,
+            value
+        );
+    }
+
+    assert!(double("2.0 arcSin").is_nan());
+    assert!(double("-2.0 arcCos").is_nan());
+}
+
+#[test]
+fn string_pad_with() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("'7' leftPad: 3 with: '0'", "007"),
+        ("'7' rightPad: 3 with: '.'", "7.."),
+        ("'hello' leftPad: 3 with: ' '", "hello"),
+        ("'abc' rightPad: 3 with: '-'", "abc"),
+        ("'été' leftPad: 4 with: '*'", "*été"),
+        ("#sym rightPad: 5 with: '_'", "sym__"),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::String(Rc::new(expected.to_string())),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "the width exceeds the maximum of 16777216 characters")]
+fn string_pad_with_huge_width() {
+    let mut universe = setup_universe();
+
+    evaluate(&mut universe, "'7' leftPad: 1000000000000000 with: '0'");
+}
+
+#[test]
+fn smalltalk_global_dictionary() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "Smalltalk at: #Integer"),
+        Value::Class(universe.integer_class())
+    );
+    assert_eq!(
+        evaluate(&mut universe, "Smalltalk at: #NoSuchGlobal"),
+        Value::Nil
+    );
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "Smalltalk at: #Answer put: 42. ^ (Smalltalk at: #Answer) + (system global: #Answer)"
+        ),
+        Value::Integer(84)
+    );
+}
+
+#[test]
+fn array_shuffle() {
+    let mut universe = setup_universe();
+
+    let mut shuffled = |body: &str| match evaluate_method(&mut universe, body) {
+        Value::Array(values) => values.borrow().clone(),
+        value => panic!("unexpected value: {:?}", value),
+    };
+
+    let setup = "| a | a := Array new: 20. 1 to: 20 do: [ :i | a at: i put: i ]. ";
+    let first = shuffled(&format!("{} ^ a shuffle: 7", setup));
+    let second = shuffled(&format!("{} ^ a shuffle: 7", setup));
+    let other = shuffled(&format!("{} ^ a shuffle: 8", setup));
+    let receiver = shuffled(&format!("{} a shuffle: 7. ^ a", setup));
+
+    let original: Vec<_> = (1..=20).map(Value::Integer).collect();
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+    assert_ne!(first, original);
+    assert_eq!(receiver, original);
+
+    let mut sorted = first;
+    sorted.sort_by_key(|value| match value {
+        Value::Integer(value) => *value,
+        value => panic!("unexpected element: {:?}", value),
+    });
+    assert_eq!(sorted, original);
+}
+
+#[test]
+fn string_comparison() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("'abc' < 'abd'", true),
+        ("'b' < 'a'", false),
+        ("'ab' < 'abc'", true),
+        ("'abc' < 'abc'", false),
+        ("'abc' <= 'abc'", true),
+        ("'b' <= 'a'", false),
+        ("'b' > 'a'", true),
+        ("'a' > 'a'", false),
+        ("'a' >= 'a'", true),
+        ("'Z' < 'a'", true),
+        ("#abc < 'abd'", true),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::Boolean(*expected),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn string_comparison_sorts_strings() {
+    let mut universe = setup_universe();
+
+    // The core library has no `asSortedArray`, so this insertion-sorts through `String>>#<`.
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| words sorted | words := #('pear' 'apple' 'fig' 'banana' 'apricot'). 2 to: words length do: [ :i | | word j | word := words at: i. j := i - 1. [ j > 0 and: [ word < (words at: j) ] ] whileTrue: [ words at: j + 1 put: (words at: j). j := j - 1 ]. words at: j + 1 put: word ]. sorted := ''. words do: [ :word | sorted := sorted + word + ' ' ]. ^ sorted"
+        ),
+        Value::String(Rc::new(String::from("apple apricot banana fig pear ")))
+    );
+}
+
+#[test]
+fn universe_install_class_extension() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    load_class(
+        &mut universe,
+        "Counter = ( | count | reset = ( count := 5 ) )",
+        &object_class,
+    );
+
+    universe
+        .install_class_extension("Integer", "double = ( ^self * 2 ) ---- answer = ( ^42 )")
+        .expect("could not install class extension");
+    universe
+        .install_class_extension("Integer", "abs = ( ^7 )")
+        .expect("could not install class extension");
+    universe
+        .install_class_extension("Counter", "count = ( ^count )")
+        .expect("could not install class extension");
+
+    assert_eq!(evaluate(&mut universe, "21 double"), Value::Integer(42));
+    assert_eq!(
+        evaluate(&mut universe, "Integer answer"),
+        Value::Integer(42)
+    );
+    assert_eq!(evaluate(&mut universe, "-3 abs"), Value::Integer(7));
+    assert_eq!(
+        evaluate_method(&mut universe, "| c | c := Counter new. c reset. ^ c count"),
+        Value::Integer(5)
+    );
+
+    assert!(universe
+        .install_class_extension("Counter", "| extra | extra = ( ^extra )")
+        .is_err());
+    assert!(universe
+        .install_class_extension("NoSuchClass", "foo = ( ^1 )")
+        .is_err());
+}
+
+#[test]
+fn double_equality() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("2.0 = 2", true),
+        ("2.5 = 2", false),
+        ("2.0 = 2.0", true),
+        ("100000000000000000000.0 = 100000000000000000000", true),
+        ("2.0 = '2'", false),
+        ("2.0 = nil", false),
+        ("(Double PositiveInfinity - Double PositiveInfinity) = (Double PositiveInfinity - Double PositiveInfinity)", false),
+        ("(Double PositiveInfinity - Double PositiveInfinity) = 0", false),
+        ("Double PositiveInfinity = Double PositiveInfinity", true),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::Boolean(*expected),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn system_load_module() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("(system loadModule: #Array) == Array", Value::Boolean(true)),
+        (
+            "((system loadModule: #Array) new: 3) length",
+            Value::Integer(3),
+        ),
+        (
+            "((system loadModule: 'Array') new: 2) length",
+            Value::Integer(2),
+        ),
+        ("system loadModule: #NoSuchModule", Value::Nil),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            *expected,
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn array_partition() {
+    let mut universe = setup_universe();
+
+    fn array(values: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(values)))
+    }
+
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 3 4) partition: [ :e | e % 2 = 0 ]"),
+        array(vec![
+            array(vec![Value::Integer(2), Value::Integer(4)]),
+            array(vec![Value::Integer(1), Value::Integer(3)]),
+        ])
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#() partition: [ :e | true ]"),
+        array(vec![array(vec![]), array(vec![])])
+    );
+}
+
+#[test]
+fn integer_as_string_cache() {
+    let mut universe = setup_universe();
+
+    let as_string = |universe: &mut Universe, expr: &str| match evaluate(universe, expr) {
+        Value::String(value) => value,
+        value => panic!("unexpected result for '{}': {:?}", expr, value),
+    };
+
+    let first = as_string(&mut universe, "5 asString");
+    let second = as_string(&mut universe, "5 asString");
+    assert_eq!(first.as_str(), "5");
+    assert!(Rc::ptr_eq(&first, &second));
+
+    let first = as_string(&mut universe, "1000 asString");
+    let second = as_string(&mut universe, "1000 asString");
+    assert_eq!(first, second);
+    assert!(!Rc::ptr_eq(&first, &second));
+}
+
+#[test]
+fn does_not_understand_proxy() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    load_class(
+        &mut universe,
+        "Proxy = ( | selector arguments | doesNotUnderstand: sel arguments: args = ( selector := sel. arguments := args. ^ #sentinel ) selector = ( ^ selector ) arguments = ( ^ arguments ) )",
+        &object_class,
+    );
+
+    let sentinel = universe.intern_symbol("sentinel");
+    let frobnicate = universe.intern_symbol("frobnicate");
+    let at_put = universe.intern_symbol("at:put:");
+    assert_eq!(
+        evaluate(&mut universe, "Proxy new frobnicate"),
+        Value::Symbol(sentinel)
+    );
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| p | p := Proxy new. p frobnicate. ^ p selector"
+        ),
+        Value::Symbol(frobnicate)
+    );
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| p | p := Proxy new. p at: 1 put: 2. ^ p selector"
+        ),
+        Value::Symbol(at_put)
+    );
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "| p | p := Proxy new. p at: 1 put: 2. ^ p arguments"
+        ),
+        Value::Array(Rc::new(RefCell::new(vec![
+            Value::Integer(1),
+            Value::Integer(2)
+        ])))
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "| p | p := Proxy new. ^ p + 3"),
+        Value::Symbol(sentinel)
+    );
+}
+
+#[test]
+fn array_remove_duplicates() {
+    let mut universe = setup_universe();
+
+    fn array(values: Vec<Value>) -> Value {
+        Value::Array(Rc::new(RefCell::new(values)))
+    }
+
+    assert_eq!(
+        evaluate(&mut universe, "#(1 2 1 3 2) removeDuplicates"),
+        array(vec![
+            Value::Integer(1),
+            Value::Integer(2),
+            Value::Integer(3)
+        ])
+    );
+    assert_eq!(
+        evaluate(&mut universe, "#() removeDuplicates"),
+        array(vec![])
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "| a | a := #(1 1). a removeDuplicates. ^ a"),
+        array(vec![Value::Integer(1), Value::Integer(1)])
+    );
+}
+
+#[test]
+fn string_join() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("', ' join: #('a' 'b' 'c')", "a, b, c"),
+        ("'-' join: #()", ""),
+        ("'' join: #('x' 'y')", "xy"),
+        ("', ' join: #('a' 1 2.5 #b)", "a, 1, 2.5, #b"),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            Value::String(Rc::new(expected.to_string())),
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn integer_division() {
+    let mut universe = setup_universe();
+
+    let tests = &[
+        ("7 / 2", Value::Integer(3)),
+        ("-7 / 2", Value::Integer(-3)),
+        ("7 / -2", Value::Integer(-3)),
+        (
+            "(100000000000000000000 / 3) = 33333333333333333333",
+            Value::Boolean(true),
+        ),
+        ("7 // 2", Value::Double(3.5)),
+        ("-7 // 2", Value::Double(-3.5)),
+    ];
+
+    for (expr, expected) in tests {
+        assert_eq!(
+            evaluate(&mut universe, expr),
+            *expected,
+            "unexpected result for '{}'",
+            expr
+        );
+    }
+}
+
+#[test]
+fn object_assert() {
+    let mut universe = setup_universe();
+
+    assert_eq!(
+        evaluate(&mut universe, "(3 assert: true) + 1"),
+        Value::Integer(4)
+    );
+    assert_eq!(
+        evaluate(&mut universe, "3 assert: 1 < 2 description: 'unused'"),
+        Value::Integer(3)
+    );
+}
+
+#[test]
+fn object_this_context() {
+    let mut universe = setup_universe();
+
+    let object_class = universe.object_class();
+    load_class(
+        &mut universe,
+        "ContextHelper = ( inner = ( ^ self thisContext ) )",
+        &object_class,
+    );
+    load_class(
+        &mut universe,
+        "Context = ( answer = ( ^ 42 ) )",
+        &object_class,
+    );
+
+    let run = universe.intern_symbol("run");
+    let inner = universe.intern_symbol("inner");
+    assert_eq!(
+        evaluate_method(&mut universe, "^ self thisContext signature"),
+        Value::Symbol(run)
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "^ self thisContext sender"),
+        Value::Nil
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "^ ContextHelper new inner signature"),
+        Value::Symbol(inner)
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "^ ContextHelper new inner sender signature"),
+        Value::Symbol(run)
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "^ [ self thisContext ] value signature"),
+        Value::Symbol(run)
+    );
+    assert_eq!(
+        evaluate_method(&mut universe, "^ [ self thisContext ] value sender sender"),
+        Value::Nil
+    );
+    match evaluate_method(&mut universe, "^ self thisContext bytecodeIndex") {
+        Value::Integer(idx) => assert!(idx > 0, "unexpected bytecode index: {}", idx),
+        value => panic!("unexpected value: {:?}", value),
+    }
+
+    // Reifying frames must not have replaced the user-defined `Context` class.
+    assert_eq!(
+        evaluate_method(
+            &mut universe,
+            "self thisContext signature. ^ Context new answer"
+        ),
+        Value::Integer(42)
+    );
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "stack-maps")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use som_interpreter_bc::class::Class;
+use som_interpreter_bc::frame::FrameKind;
+use som_interpreter_bc::interpreter::Interpreter;
+use som_interpreter_bc::method::{Method, MethodKind};
+use som_interpreter_bc::universe::Universe;
+use som_interpreter_bc::value::Value;
+use som_interpreter_bc::SOMRef;
+
+fn setup_universe() -> Universe {
+    let classpath = vec![PathBuf::from("../core-lib/Smalltalk")];
+    Universe::with_classpath(classpath).expect("could not setup test universe")
+}
+
+/// Load a test class, and get its (class-side) `run` method, along with the class which holds it.
+fn load_method(universe: &mut Universe, name: &str, source: &str) -> (SOMRef<Class>, Rc<Method>) {
+    let directory = std::env::temp_dir().join("som-interpreter-bc-stack-map-tests");
+    fs::create_dir_all(&directory).expect("could not create test directory");
+    let file = directory.join(format!("{}.som", name));
+    fs::write(&file, source).expect("could not write test class");
+
+    let class = universe
+        .load_class_from_path(&file)
+        .expect("could not load test class");
+    let signature = universe.intern_symbol("run");
+    let method = class
+        .borrow()
+        .class()
+        .borrow()
+        .lookup_method(signature)
+        .expect("could not find 'run'");
+    (class, method)
+}
+
+fn run(universe: &mut Universe, method: Rc<Method>) -> Option<Value> {
+    let holder = method.holder().upgrade().unwrap();
+    let mut interpreter = Interpreter::new();
+    let frame = interpreter.push_frame(FrameKind::Method {
+        method,
+        holder: holder.clone(),
+        self_value: Value::Class(holder.clone()),
+    });
+    frame.borrow_mut().args.push(Value::Class(holder));
+    interpreter.run(universe)
+}
+
+#[test]
+fn stack_map_matches_execution() {
+    let mut universe = setup_universe();
+
+    let (_class, method) = load_method(
+        &mut universe,
+        "StackMapCorrect",
+        "StackMapCorrect = ( ---- run = ( | a | a := 3 + 4. ^ #(1 2) inject: a into: [ :x :y | x + y ] ) )",
+    );
+
+    match method.kind() {
+        MethodKind::Defined(env) => {
+            assert_eq!(env.stack_map.len(), env.body.len());
+            assert_eq!(env.stack_map[..4], [0, 1, 2, 1]);
+        }
+        _ => panic!("'run' was not compiled"),
+    }
+
+    assert_eq!(run(&mut universe, method), Some(Value::Integer(10)));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "stack height mismatch before bytecode 1")]
+fn corrupted_stack_map_is_detected() {
+    let mut universe = setup_universe();
+
+    let (_class, method) = load_method(
+        &mut universe,
+        "StackMapCorrupted",
+        "StackMapCorrupted = ( ---- run = ( ^ 3 + 4 ) )",
+    );
+
+    let mut corrupted = Method::clone(&method);
+    match &mut corrupted.kind {
+        MethodKind::Defined(env) => env.stack_map[1] += 1,
+        _ => panic!("'run' was not compiled"),
+    }
+
+    run(&mut universe, Rc::new(corrupted));
+}
@@ -1,8 +1,31 @@
+use std::cell::Cell;
+
 use som_core::ast::*;
 use som_lexer::Token;
 use som_parser_core::combinators::*;
 use som_parser_core::Parser;
 
+thread_local! {
+    /// The smallest amount of remaining input that any token parser has looked at, used to locate parse errors.
+    static FURTHEST_REMAINING: Cell<usize> = const { Cell::new(usize::MAX) };
+}
+
+/// Forget how far into their input the token parsers have looked.
+pub(crate) fn reset_furthest_remaining() {
+    FURTHEST_REMAINING.with(|furthest| furthest.set(usize::MAX));
+}
+
+/// Get the smallest amount of remaining input that any token parser has looked at, since the last reset.
+pub(crate) fn furthest_remaining() -> usize {
+    FURTHEST_REMAINING.with(Cell::get)
+}
+
+/// Splits the next token from the input, recording how far into the input the parser has looked.
+fn next_token(input: &[Token]) -> Option<(&Token, &[Token])> {
+    FURTHEST_REMAINING.with(|furthest| furthest.set(furthest.get().min(input.len())));
+    input.split_first()
+}
+
 macro_rules! opaque {
     ($expr:expr) => {{
         move |input: &'a [Token]| $expr.parse(input)
@@ -12,7 +35,7 @@ macro_rules! opaque {
 /// A parser that expects to be nothing left in its input.
 pub fn eof<'a>() -> impl Parser<(), &'a [Token]> {
     move |input: &'a [Token]| {
-        if input.is_empty() {
+        if next_token(input).is_none() {
             Some(((), input))
         } else {
             None
@@ -22,7 +45,7 @@ pub fn eof<'a>() -> impl Parser<(), &'a [Token]> {
 
 pub fn exact<'a>(ch: Token) -> impl Parser<(), &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         if *head == ch {
             Some(((), tail))
         } else {
@@ -33,7 +56,7 @@ pub fn exact<'a>(ch: Token) -> impl Parser<(), &'a [Token]> {
 
 pub fn exact_ident<'a, 'b: 'a>(string: &'b str) -> impl Parser<(), &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::Identifier(ref ident) if ident.as_str() == string => Some(((), tail)),
             _ => None,
@@ -46,7 +69,7 @@ pub fn big_integer<'a>() -> impl Parser<String, &'a [Token]> {
         let (sign, input) = optional(exact(Token::Minus)).parse(input)?;
         let sign = if sign.is_some() { "-" } else { "" };
 
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::LitBigInteger(value) => Some((format!("{}{}", sign, value), tail)),
             _ => None,
@@ -59,7 +82,7 @@ pub fn integer<'a>() -> impl Parser<i64, &'a [Token]> {
         let (sign, input) = optional(exact(Token::Minus)).parse(input)?;
         let sign = if sign.is_some() { -1 } else { 1 };
 
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::LitInteger(value) => Some((*value * sign, tail)),
             _ => None,
@@ -72,7 +95,7 @@ pub fn double<'a>() -> impl Parser<f64, &'a [Token]> {
         let (sign, input) = optional(exact(Token::Minus)).parse(input)?;
         let sign = if sign.is_some() { -1.0 } else { 1.0 };
 
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::LitDouble(value) => Some((*value * sign, tail)),
             _ => None,
@@ -82,7 +105,7 @@ pub fn double<'a>() -> impl Parser<f64, &'a [Token]> {
 
 pub fn single_operator<'a>() -> impl Parser<&'static str, &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::Not => Some(("~", tail)),
             Token::And => Some(("&", tail)),
@@ -105,7 +128,7 @@ pub fn single_operator<'a>() -> impl Parser<&'static str, &'a [Token]> {
 
 pub fn operator_sequence<'a>() -> impl Parser<String, &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::OperatorSequence(seq) => Some((seq.clone(), tail)),
             _ => None,
@@ -119,7 +142,7 @@ pub fn operator<'a>() -> impl Parser<String, &'a [Token]> {
 
 pub fn identifier<'a>() -> impl Parser<String, &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::Identifier(value) => Some((value.clone(), tail)),
             _ => None,
@@ -129,7 +152,7 @@ pub fn identifier<'a>() -> impl Parser<String, &'a [Token]> {
 
 pub fn string<'a>() -> impl Parser<String, &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::LitString(value) => Some((value.clone(), tail)),
             _ => None,
@@ -137,9 +160,19 @@ pub fn string<'a>() -> impl Parser<String, &'a [Token]> {
     }
 }
 
+pub fn comment<'a>() -> impl Parser<String, &'a [Token]> {
+    move |input: &'a [Token]| {
+        let (head, tail) = next_token(input)?;
+        match head {
+            Token::Comment(value) => Some((value.clone(), tail)),
+            _ => None,
+        }
+    }
+}
+
 pub fn symbol<'a>() -> impl Parser<String, &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::LitSymbol(value) => Some((value.clone(), tail)),
             _ => None,
@@ -151,13 +184,18 @@ pub fn array<'a>() -> impl Parser<Vec<Literal>, &'a [Token]> {
     move |input: &'a [Token]| {
         between(
             exact(Token::NewArray),
-            many(literal()),
+            many(literal().or(array_symbol().map(Literal::Symbol))),
             exact(Token::EndTerm),
         )
         .parse(input)
     }
 }
 
+/// Bare words within a literal array are shorthands for symbols (eg. `#(foo at:put:)`).
+pub fn array_symbol<'a>() -> impl Parser<String, &'a [Token]> {
+    identifier().or(some(keyword()).map(|keywords| keywords.concat()))
+}
+
 pub fn literal<'a>() -> impl Parser<Literal, &'a [Token]> {
     (double().map(Literal::Double))
         .or(integer().map(Literal::Integer))
@@ -169,7 +207,7 @@ pub fn literal<'a>() -> impl Parser<Literal, &'a [Token]> {
 
 pub fn keyword<'a>() -> impl Parser<String, &'a [Token]> {
     move |input: &'a [Token]| {
-        let (head, tail) = input.split_first()?;
+        let (head, tail) = next_token(input)?;
         match head {
             Token::Keyword(value) => Some((value.clone(), tail)),
             _ => None,
@@ -357,26 +395,35 @@ pub fn class_def<'a>() -> impl Parser<ClassDef, &'a [Token]> {
         .and(optional(identifier()))
         .and(between(
             exact(Token::NewTerm),
-            default(locals()).and(many(method_def())).and(default(
-                exact(Token::Separator).and_right(default(locals()).and(many(method_def()))),
-            )),
+            optional(comment())
+                .and(default(locals()).and(many(method_def())))
+                .and(default(
+                    exact(Token::Separator).and_right(default(locals()).and(many(method_def()))),
+                )),
             exact(Token::EndTerm),
         ))
-        .map(|((name, super_class), (instance_defns, static_defns))| {
-            let (instance_locals, instance_methods) = instance_defns;
-            let (static_locals, static_methods) = static_defns;
-
-            ClassDef {
-                name,
-                super_class,
-                instance_locals,
-                instance_methods,
-                static_locals,
-                static_methods,
-            }
-        })
+        .map(
+            |((name, super_class), ((comment, instance_defns), static_defns))| {
+                let (instance_locals, instance_methods) = instance_defns;
+                let (static_locals, static_methods) = static_defns;
+
+                ClassDef {
+                    name,
+                    super_class,
+                    comment,
+                    instance_locals,
+                    instance_methods,
+                    static_locals,
+                    static_methods,
+                }
+            },
+        )
 }
 
 pub fn file<'a>() -> impl Parser<ClassDef, &'a [Token]> {
     class_def().and_left(eof())
 }
+
+pub fn classes<'a>() -> impl Parser<Vec<ClassDef>, &'a [Token]> {
+    many(class_def()).and_left(eof())
+}
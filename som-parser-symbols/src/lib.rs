@@ -7,13 +7,81 @@
 /// SOM-specific parser combinators.
 pub mod lang;
 
+use std::error::Error;
+use std::fmt;
+
 use som_core::ast::ClassDef;
 use som_lexer::Token;
 use som_parser_core::Parser;
 
+/// The location at which a parser stopped making progress through its input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The index of the token at which parsing stalled (the input's length, if it ran out of tokens).
+    pub position: usize,
+    /// The last token successfully consumed before that position, if any.
+    pub last_token: Option<Token>,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.last_token {
+            Some(token) => write!(
+                f,
+                "could not parse the input at token {} (after {:?})",
+                self.position, token
+            ),
+            None => write!(f, "could not parse the input at token {}", self.position),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
 /// Parses the input of an entire file into an AST.
+///
+/// Comments are discarded, except for the one opening the class' body, which is kept as the class' comment.
 pub fn parse_file(input: &[Token]) -> Option<ClassDef> {
-    self::apply(lang::file(), input)
+    let class_comment = input
+        .iter()
+        .position(|token| *token == Token::NewTerm)
+        .map(|idx| idx + 1);
+    let tokens = strip_comments(input, |idx| Some(idx) == class_comment);
+    self::apply(lang::file(), tokens.as_slice())
+}
+
+/// Parses the input of an entire file, made of any number of consecutive class definitions, into ASTs.
+///
+/// Comments are discarded, except for the ones opening each class' body, which are kept as the classes' comments.
+pub fn parse_classes(input: &[Token]) -> Option<Vec<ClassDef>> {
+    let mut class_comments = Vec::new();
+    let mut depth = 0usize;
+    for (idx, token) in input.iter().enumerate() {
+        match token {
+            Token::NewTerm => {
+                if depth == 0 {
+                    class_comments.push(idx + 1);
+                }
+                depth += 1;
+            }
+            // Literal arrays are closed by the same `)` as terms.
+            Token::NewArray => depth += 1,
+            Token::EndTerm => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    let tokens = strip_comments(input, |idx| class_comments.contains(&idx));
+    self::apply(lang::classes(), tokens.as_slice())
+}
+
+/// Removes the comments from the input, except for the ones at the positions accepted by `keep`.
+fn strip_comments(input: &[Token], keep: impl Fn(usize) -> bool) -> Vec<Token> {
+    input
+        .iter()
+        .enumerate()
+        .filter(|(idx, token)| !matches!(token, Token::Comment(_)) || keep(*idx))
+        .map(|(_, token)| token.clone())
+        .collect()
 }
 
 /// Applies a parser and returns the output value if the entirety of the input has been parsed successfully.
@@ -26,3 +94,22 @@ where
         Some(_) | None => None,
     }
 }
+
+/// Applies a parser and returns the output value if the entirety of the input has been parsed successfully,
+/// or the location at which it stalled otherwise.
+pub fn apply_with_error<'a, A, P>(mut parser: P, input: &'a [Token]) -> Result<A, ParseError>
+where
+    P: Parser<A, &'a [Token]>,
+{
+    lang::reset_furthest_remaining();
+    let remaining = match parser.parse(input) {
+        Some((output, [])) => return Ok(output),
+        Some((_, tail)) => tail.len().min(lang::furthest_remaining()),
+        None => lang::furthest_remaining(),
+    };
+    let position = input.len() - remaining.min(input.len());
+    Err(ParseError {
+        position,
+        last_token: position.checked_sub(1).map(|idx| input[idx].clone()),
+    })
+}
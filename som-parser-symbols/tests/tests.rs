@@ -24,6 +24,36 @@ fn literal_tests() {
     assert_eq!(iter.next(), None);
 }
 
+#[test]
+fn repetition_tests() {
+    let tokens: Vec<Token> = Lexer::new("foo bar baz.").skip_whitespace(true).collect();
+
+    let (identifiers, rest) = many(identifier())
+        .parse(tokens.as_slice())
+        .expect("input did not parse successfully");
+    assert_eq!(identifiers, ["foo", "bar", "baz"]);
+    assert_eq!(rest, [Token::Period]);
+
+    let (identifiers, rest) = many(identifier())
+        .parse(rest)
+        .expect("`many` should accept zero repetitions");
+    assert!(identifiers.is_empty());
+    assert_eq!(rest, [Token::Period]);
+
+    assert!(
+        some(identifier()).parse(rest).is_none(),
+        "`some` should require at least one repetition"
+    );
+
+    let tokens: Vec<Token> = Lexer::new("foo. bar. baz").skip_whitespace(true).collect();
+
+    let (identifiers, rest) = sep_by(exact(Token::Period), identifier())
+        .parse(tokens.as_slice())
+        .expect("input did not parse successfully");
+    assert!(rest.is_empty(), "input did not parse in its entirety");
+    assert_eq!(identifiers, ["foo", "bar", "baz"]);
+}
+
 #[test]
 fn expression_test_1() {
     let tokens: Vec<Token> = Lexer::new("3 + counter get")
@@ -213,3 +243,199 @@ fn primary_test() {
         }),
     );
 }
+
+#[test]
+fn literal_array_test() {
+    let tokens: Vec<Token> = Lexer::new("#(#foo bar at:put: 42 'str' #(1 #baz qux))")
+        .skip_whitespace(true)
+        .collect();
+
+    let result = literal().parse(tokens.as_slice());
+
+    assert!(result.is_some(), "input did not parse successfully");
+    let (literal, rest) = result.unwrap();
+    assert!(rest.is_empty(), "input did not parse in its entirety");
+
+    assert_eq!(
+        literal,
+        Literal::Array(vec![
+            Literal::Symbol(String::from("foo")),
+            Literal::Symbol(String::from("bar")),
+            Literal::Symbol(String::from("at:put:")),
+            Literal::Integer(42),
+            Literal::String(String::from("str")),
+            Literal::Array(vec![
+                Literal::Integer(1),
+                Literal::Symbol(String::from("baz")),
+                Literal::Symbol(String::from("qux")),
+            ]),
+        ])
+    );
+}
+
+#[test]
+fn method_parameter_count_test() {
+    let parse = |source: &str| {
+        let tokens: Vec<Token> = Lexer::new(source).skip_whitespace(true).collect();
+        som_parser_symbols::parse_file(tokens.as_slice())
+    };
+
+    let class_def = parse("Foo = ( at: index put: value = ( ^ value ) + other = ( ^ other ) )")
+        .expect("well-formed methods did not parse");
+    assert_eq!(
+        class_def.instance_methods[0].kind,
+        MethodKind::Positional {
+            parameters: vec![String::from("index"), String::from("value")],
+        }
+    );
+    assert_eq!(class_def.instance_methods[0].signature, "at:put:");
+
+    assert!(
+        parse("Foo = ( at:put: x = ( ^ x ) )").is_none(),
+        "a keyword method with too few parameters was accepted"
+    );
+    assert!(
+        parse("Foo = ( at: x y = ( ^ x ) )").is_none(),
+        "a keyword method with too many parameters was accepted"
+    );
+    assert!(
+        parse("Foo = ( + = ( ^ self ) )").is_none(),
+        "a binary method without a parameter was accepted"
+    );
+    assert!(
+        parse("Foo = ( + x y = ( ^ x ) )").is_none(),
+        "a binary method with two parameters was accepted"
+    );
+}
+
+#[test]
+fn multiple_classes_test() {
+    let tokens: Vec<Token> = Lexer::new(
+        r#"Foo = ( "the foo class" run = ( "not a class comment" ^ 1 ) ) Bar = Foo ( "the bar class" | x | )"#,
+    )
+    .skip_whitespace(true)
+    .collect();
+
+    let classes =
+        som_parser_symbols::parse_classes(tokens.as_slice()).expect("input did not parse");
+    assert_eq!(classes.len(), 2);
+    assert_eq!(classes[0].name, "Foo");
+    assert_eq!(classes[0].super_class, None);
+    assert_eq!(classes[0].comment.as_deref(), Some("the foo class"));
+    assert_eq!(classes[0].instance_methods[0].signature, "run");
+    assert_eq!(classes[1].name, "Bar");
+    assert_eq!(classes[1].super_class.as_deref(), Some("Foo"));
+    assert_eq!(classes[1].comment.as_deref(), Some("the bar class"));
+    assert_eq!(classes[1].instance_locals, ["x"]);
+
+    assert!(
+        som_parser_symbols::parse_file(tokens.as_slice()).is_none(),
+        "`parse_file` accepted more than one class"
+    );
+
+    // A literal array must not throw off the tracking of the classes' bodies.
+    let tokens: Vec<Token> = Lexer::new(r#"A = ( foo = ( ^ #(1) ) bar = ( "doc" ^ 1 ) ) B = ( )"#)
+        .skip_whitespace(true)
+        .collect();
+    let classes =
+        som_parser_symbols::parse_classes(tokens.as_slice()).expect("input did not parse");
+    assert_eq!(classes.len(), 2);
+    assert_eq!(classes[0].instance_methods.len(), 2);
+}
+
+#[test]
+fn keyword_arguments_repetition_test() {
+    let tokens: Vec<Token> = Lexer::new("at: 1 put: 'two'.")
+        .skip_whitespace(true)
+        .collect();
+
+    let (arguments, rest) = some(keyword().and(literal()))
+        .parse(tokens.as_slice())
+        .expect("input did not parse successfully");
+    assert_eq!(
+        arguments,
+        [
+            (String::from("at:"), Literal::Integer(1)),
+            (String::from("put:"), Literal::String(String::from("two"))),
+        ]
+    );
+    assert_eq!(rest, [Token::Period]);
+    assert!(
+        some(keyword().and(literal())).parse(rest).is_none(),
+        "`some` should require at least one repetition"
+    );
+
+    let tokens: Vec<Token> = Lexer::new("x: 1. y: 2.").skip_whitespace(true).collect();
+
+    let (arguments, rest) = sep_by(exact(Token::Period), keyword().and(literal()))
+        .parse(tokens.as_slice())
+        .expect("input did not parse successfully");
+    assert_eq!(
+        arguments,
+        [
+            (String::from("x:"), Literal::Integer(1)),
+            (String::from("y:"), Literal::Integer(2)),
+        ]
+    );
+    assert_eq!(rest, [Token::Period], "the trailing separator was consumed");
+
+    let (arguments, rest) = sep_by(exact(Token::Period), keyword().and(literal()))
+        .parse(rest)
+        .expect("`sep_by` should accept zero repetitions");
+    assert!(arguments.is_empty());
+    assert_eq!(rest, [Token::Period]);
+}
+
+#[test]
+fn parse_error_position_test() {
+    let parse = |source: &str| {
+        let tokens: Vec<Token> = Lexer::new(source).skip_whitespace(true).collect();
+        let result = som_parser_symbols::apply_with_error(file(), tokens.as_slice());
+        (tokens, result)
+    };
+
+    let (tokens, result) = parse("Foo = ( run = ( ^ 1 )");
+    let error = result.expect_err("a class missing its closing parenthesis was accepted");
+    assert_eq!(error.position, tokens.len());
+    assert_eq!(error.last_token, Some(Token::EndTerm));
+
+    let (_, result) = parse("Foo = ( run = ( ^ 1 + ) )");
+    let error = result.expect_err("a binary send missing its argument was accepted");
+    assert_eq!(error.position, 9);
+    assert_eq!(error.last_token, Some(Token::Plus));
+
+    let (_, result) = parse("Foo = ( run = ( ^ 1 ) )");
+    let class_def = result.expect("a well-formed class did not parse");
+    assert_eq!(class_def.name, "Foo");
+}
+
+#[test]
+fn optional_superclass_test() {
+    let tokens: Vec<Token> = Lexer::new("Foo = Bar ( run = ( ^ 1 ) )")
+        .skip_whitespace(true)
+        .collect();
+    let class = som_parser_symbols::apply(class_def(), tokens.as_slice())
+        .expect("a class with a superclass did not parse");
+    assert_eq!(class.name, "Foo");
+    assert_eq!(class.super_class.as_deref(), Some("Bar"));
+
+    let tokens: Vec<Token> = Lexer::new("Foo = ( run = ( ^ 1 ) ---- new = ( ^ 2 ) )")
+        .skip_whitespace(true)
+        .collect();
+    let class = som_parser_symbols::apply(class_def(), tokens.as_slice())
+        .expect("a class without a superclass did not parse");
+    assert_eq!(class.name, "Foo");
+    assert_eq!(class.super_class, None);
+    assert_eq!(class.static_methods[0].signature, "new");
+
+    let tokens: Vec<Token> = Lexer::new("foo bar").skip_whitespace(true).collect();
+    let (value, rest) = optional(identifier().and(exact(Token::Equal)))
+        .parse(tokens.as_slice())
+        .expect("`optional` should always succeed");
+    assert_eq!(value, None);
+    assert_eq!(
+        rest,
+        tokens.as_slice(),
+        "a failed optional parse consumed input"
+    );
+}
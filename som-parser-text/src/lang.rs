@@ -525,6 +525,8 @@ pub fn class_def<'a>() -> impl Parser<ClassDef, &'a [char]> {
         let (_, input) = many(spacing()).parse(input)?;
         let (super_class, input) = optional(identifier().and_left(many(spacing()))).parse(input)?;
         let (_, input) = exact('(').parse(input)?;
+        let (_, input) = many(whitespace()).parse(input)?;
+        let (comment, input) = optional(comment()).parse(input)?;
         let (_, input) = many(spacing()).parse(input)?;
         let ((instance_locals, instance_methods), input) = class_section.parse(input)?;
         let (_, input) = many(spacing()).parse(input)?;
@@ -536,6 +538,7 @@ pub fn class_def<'a>() -> impl Parser<ClassDef, &'a [char]> {
         let class_def = ClassDef {
             name,
             super_class,
+            comment,
             instance_locals,
             instance_methods,
             static_locals,